@@ -0,0 +1,345 @@
+// wasm/src/batch.rs
+// Batch checks of measurement campaigns: file pattern -> standard rules, per-file verdicts, campaign statistics
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::measurement::parse_measurement;
+use crate::{ComplianceSummary, Detector, EMCStandard, EmcError, SelectionParameters};
+
+// Standard to apply to files whose name matches the pattern ('*' and '?' wildcards)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRule {
+    pub pattern: String,
+    pub standard: String,
+    pub class: String,
+    #[serde(default = "default_detector")]
+    pub detector: Detector,
+    #[serde(default)]
+    pub parameters: SelectionParameters,
+    #[serde(default)]
+    pub frequency_unit: Option<String>,
+}
+
+fn default_detector() -> Detector {
+    Detector::Qp
+}
+
+impl BatchRule {
+    // Identifies the rule's standard in file results and tallies, also when it fails to load
+    pub fn label(&self) -> String {
+        format!("{} {}", self.standard, self.class)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    Pass,
+    Fail,
+    Error,     // unreadable file, unknown standard, or no point within the limits
+    Unmapped,  // no rule matches the file name
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileResult {
+    pub file: String,
+    pub standard: Option<String>,  // "<standard key> <class>" of the matching rule
+    pub detector: Option<Detector>,
+    pub verdict: Verdict,
+    #[serde(flatten)]
+    pub summary: Option<ComplianceSummary>,
+    pub error: Option<String>,
+}
+
+impl FileResult {
+    pub fn error(file: &str, rule: Option<&BatchRule>, message: String) -> FileResult {
+        FileResult {
+            file: file.to_string(),
+            standard: rule.map(BatchRule::label),
+            detector: rule.map(|r| r.detector),
+            verdict: Verdict::Error,
+            summary: None,
+            error: Some(message),
+        }
+    }
+
+    fn worst_margin(&self) -> Option<f64> {
+        self.summary.as_ref().map(|s| s.worst_margin).filter(|m| !m.is_nan())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandardTally {
+    pub standard: String,
+    pub files: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub worst_margin: f64,  // dB
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignStatistics {
+    pub files: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    pub unmapped: usize,
+    pub pass_rate: f64,          // % of checked (passed + failed) files
+    pub total_violations: usize,
+    pub worst_margin: f64,       // dB, over all checked files
+    pub worst_file: Option<String>,
+    pub worst_frequency: f64,    // Hz
+    pub mean_worst_margin: f64,  // dB, mean of the per-file worst margins
+    pub by_standard: Vec<StandardTally>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchReport {
+    pub files: Vec<FileResult>,
+    pub statistics: CampaignStatistics,
+}
+
+// Case-insensitive wildcard match of a whole file name
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;  // (pattern index after '*', name index)
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p + 1, n));
+            p += 1;
+        } else if let Some((bp, bn)) = backtrack {
+            // Let the last '*' absorb one more character
+            p = bp;
+            n = bn + 1;
+            backtrack = Some((bp, bn + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+// Files of a directory, or matching a wildcard in the last path component, sorted by name
+pub fn collect_files(target: &str) -> Result<Vec<PathBuf>, EmcError> {
+    let path = Path::new(target);
+    let (dir, pattern) = if path.is_dir() {
+        (path.to_path_buf(), None)
+    } else {
+        let name = path.file_name().and_then(|n| n.to_str()).ok_or_else(|| EmcError::invalid(format!("Invalid path '{}'", target)))?;
+        let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        if parent.to_string_lossy().contains(['*', '?']) {
+            return Err(EmcError::invalid(format!("'{}': wildcards are only supported in the file name", target)));
+        }
+        if !name.contains(['*', '?']) {
+            return if path.is_file() { Ok(vec![path.to_path_buf()]) } else { Err(EmcError::invalid(format!("'{}' not found", target))) };
+        }
+        (parent.to_path_buf(), Some(name.to_string()))
+    };
+
+    let entries = std::fs::read_dir(&dir).map_err(|e| EmcError::invalid(format!("{}: {}", dir.display(), e)))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            !name.starts_with('.') && pattern.as_deref().is_none_or(|pattern| matches_pattern(pattern, name))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+// Checks files against the first matching rule; standards are loaded once per rule
+pub struct BatchRunner {
+    rules: Vec<BatchRule>,
+    standards: HashMap<usize, Result<EMCStandard, EmcError>>,
+}
+
+impl BatchRunner {
+    pub fn new(rules: Vec<BatchRule>) -> Result<BatchRunner, EmcError> {
+        if rules.is_empty() {
+            return Err(EmcError::invalid("A batch needs at least one pattern -> standard rule"));
+        }
+        Ok(BatchRunner { rules, standards: HashMap::new() })
+    }
+
+    pub fn rule_for(&self, file_name: &str) -> Option<&BatchRule> {
+        self.rules.iter().find(|rule| matches_pattern(&rule.pattern, file_name))
+    }
+
+    // `file` is the name matched against the rule patterns
+    pub fn check(&mut self, file: &str, text: &str) -> FileResult {
+        let Some(index) = self.rules.iter().position(|rule| matches_pattern(&rule.pattern, file)) else {
+            return FileResult { file: file.to_string(), standard: None, detector: None, verdict: Verdict::Unmapped, summary: None, error: None };
+        };
+        let rule = &self.rules[index];
+        let standard = self
+            .standards
+            .entry(index)
+            .or_insert_with(|| EMCStandard::get_standard_with(&rule.standard, &rule.class, &rule.parameters));
+        let standard = match standard {
+            Ok(standard) => standard,
+            Err(e) => return FileResult::error(file, Some(rule), e.to_string()),
+        };
+
+        let detector = rule.detector;
+        let measurement = match parse_measurement(text, rule.frequency_unit.as_deref()) {
+            Ok(measurement) => measurement,
+            Err(e) => return FileResult::error(file, Some(rule), e.to_string()),
+        };
+        if !standard.covers_any(&measurement.frequencies) {
+            return FileResult::error(file, Some(rule), "No measured point lies within the standard's frequency range (check the frequency unit)".to_string());
+        }
+
+        let results = measurement.trace(detector).and_then(|levels| standard.check_compliance(&measurement.frequencies, levels, detector));
+        match results {
+            Ok(results) => {
+                let summary = ComplianceSummary::from_results(&results);
+                FileResult {
+                    file: file.to_string(),
+                    standard: Some(rule.label()),
+                    detector: Some(detector),
+                    verdict: if summary.passes { Verdict::Pass } else { Verdict::Fail },
+                    summary: Some(summary),
+                    error: None,
+                }
+            }
+            Err(e) => FileResult::error(file, Some(rule), e.to_string()),
+        }
+    }
+}
+
+pub fn campaign_report(files: Vec<FileResult>) -> BatchReport {
+    let count = |verdict: Verdict| files.iter().filter(|f| f.verdict == verdict).count();
+    let (passed, failed) = (count(Verdict::Pass), count(Verdict::Fail));
+    let checked: Vec<&FileResult> = files.iter().filter(|f| matches!(f.verdict, Verdict::Pass | Verdict::Fail)).collect();
+
+    let worst = checked
+        .iter()
+        .filter_map(|f| f.worst_margin().map(|m| (m, *f)))
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+    let margins: Vec<f64> = checked.iter().filter_map(|f| f.worst_margin()).collect();
+
+    let mut by_standard: Vec<StandardTally> = Vec::new();
+    for file in &checked {
+        let name = file.standard.clone().unwrap_or_default();
+        let index = match by_standard.iter().position(|t| t.standard == name) {
+            Some(index) => index,
+            None => {
+                by_standard.push(StandardTally { standard: name, files: 0, passed: 0, failed: 0, worst_margin: f64::NAN });
+                by_standard.len() - 1
+            }
+        };
+        let tally = &mut by_standard[index];
+        tally.files += 1;
+        if file.verdict == Verdict::Pass {
+            tally.passed += 1;
+        } else {
+            tally.failed += 1;
+        }
+        if let Some(margin) = file.worst_margin() {
+            tally.worst_margin = if tally.worst_margin.is_nan() { margin } else { tally.worst_margin.min(margin) };
+        }
+    }
+
+    let statistics = CampaignStatistics {
+        files: files.len(),
+        passed,
+        failed,
+        errors: count(Verdict::Error),
+        unmapped: count(Verdict::Unmapped),
+        pass_rate: if checked.is_empty() { f64::NAN } else { 100.0 * passed as f64 / checked.len() as f64 },
+        total_violations: checked.iter().filter_map(|f| f.summary.as_ref()).map(|s| s.violations).sum(),
+        worst_margin: worst.map_or(f64::NAN, |(m, _)| m),
+        worst_file: worst.map(|(_, f)| f.file.clone()),
+        worst_frequency: worst.and_then(|(_, f)| f.summary.as_ref()).map_or(f64::NAN, |s| s.worst_frequency),
+        mean_worst_margin: if margins.is_empty() { f64::NAN } else { margins.iter().sum::<f64>() / margins.len() as f64 },
+        by_standard,
+    };
+
+    BatchReport { files, statistics }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, standard: &str, class: &str) -> BatchRule {
+        serde_json::from_value(serde_json::json!({ "pattern": pattern, "standard": standard, "class": class })).unwrap()
+    }
+
+    #[test]
+    fn test_pattern_matching() {
+        assert!(matches_pattern("*.csv", "scan_01.CSV"));
+        assert!(matches_pattern("CE_*_L?.csv", "ce_dut3_L1.csv"));
+        assert!(!matches_pattern("CE_*_L?.csv", "ce_dut3_L12.csv"));
+        assert!(matches_pattern("*a*b*", "xxaxxbxx"));
+        assert!(!matches_pattern("*a*b", "xxaxxbxx"));
+        assert!(matches_pattern("*", ""));
+    }
+
+    #[test]
+    fn test_campaign() {
+        let mut runner = BatchRunner::new(vec![rule("ce_*", "CISPR22", "ClassB"), rule("re_*", "CISPR22", "ClassX")]).unwrap();
+        let files = vec![
+            runner.check("ce_pass.csv", "150000,50\n1000000,40\n"),
+            runner.check("ce_fail.csv", "150000,80\n1000000,40\n"),
+            runner.check("re_bad_class.csv", "30e6,20\n"),
+            runner.check("ce_mhz.csv", "0.15,50\n"),
+            runner.check("notes.txt", "hello"),
+        ];
+        let verdicts: Vec<Verdict> = files.iter().map(|f| f.verdict).collect();
+        assert_eq!(verdicts, vec![Verdict::Pass, Verdict::Fail, Verdict::Error, Verdict::Error, Verdict::Unmapped]);
+
+        let report = campaign_report(files);
+        let stats = &report.statistics;
+        assert_eq!((stats.files, stats.passed, stats.failed, stats.errors, stats.unmapped), (5, 1, 1, 2, 1));
+        assert_eq!(stats.pass_rate, 50.0);
+        assert_eq!(stats.worst_file.as_deref(), Some("ce_fail.csv"));
+        assert_eq!(stats.worst_frequency, 150e3);
+        assert!((stats.worst_margin + 8.0).abs() < 1e-9);  // QP limit 72 dBµV at 150 kHz
+        assert_eq!(stats.by_standard.len(), 1);
+        assert_eq!(stats.by_standard[0].files, 2);
+        // Checked and errored files of a rule carry the same identifier
+        assert_eq!(report.files[0].standard.as_deref(), Some("CISPR22 ClassB"));
+        assert_eq!(report.files[3].standard, report.files[0].standard);
+        assert_eq!(stats.by_standard[0].standard, "CISPR22 ClassB");
+    }
+
+    #[test]
+    fn test_collect_files() {
+        let dir = std::env::temp_dir().join(format!("emc_batch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["b.csv", "a.csv", "c.txt", ".hidden.csv"] {
+            std::fs::write(dir.join(name), "1,2\n").unwrap();
+        }
+
+        let all = collect_files(dir.to_str().unwrap()).unwrap();
+        assert_eq!(all.len(), 3);
+        let csv = collect_files(dir.join("*.csv").to_str().unwrap()).unwrap();
+        let names: Vec<&str> = csv.iter().map(|p| p.file_name().unwrap().to_str().unwrap()).collect();
+        assert_eq!(names, vec!["a.csv", "b.csv"]);
+        assert!(collect_files(dir.join("missing.csv").to_str().unwrap()).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_traces_are_errors() {
+        let mut runner = BatchRunner::new(vec![rule("*", "CISPR22", "ClassB")]).unwrap();
+        let nan = runner.check("nan.csv", "150000,50\n1000000,NaN\n");
+        let unsorted = runner.check("unsorted.csv", "2000000,40\n150000,50\n");
+
+        for file in [&nan, &unsorted] {
+            assert_eq!(file.verdict, Verdict::Error, "{}", file.file);
+            assert!(file.error.as_deref().unwrap().contains("Point 1"), "{:?}", file.error);
+        }
+        assert!(unsorted.error.as_deref().unwrap().contains("ascending"));
+    }
+}
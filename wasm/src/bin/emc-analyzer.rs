@@ -0,0 +1,468 @@
+// wasm/src/bin/emc-analyzer.rs
+// Command-line analyzer for CI pipelines and test benches: compliance checks, limit masks, statistics
+use std::collections::HashMap;
+use std::process::ExitCode;
+
+use serde::Serialize;
+
+use emc_wasm::batch::{campaign_report, collect_files, BatchRule, BatchRunner, FileResult, Verdict};
+use emc_wasm::measurement::{parse_measurement, Measurement};
+use emc_wasm::validation::{validate_trace, ValidatedTrace, ValidationOptions};
+use emc_wasm::{catalog, ComplianceResult, ComplianceSummary, Detector, EMCStandard, EmcError, SelectionParameters};
+
+const USAGE: &str = "\
+Usage: emc-analyzer <command> [options]
+
+Commands:
+  check <measurement>   Compare a measured trace with a standard's limits
+  stats <measurement>   Level statistics and violations of a measured trace
+  mask                  Limit lines of a standard over a frequency range
+  batch <dir|glob>      Check every measurement file of a campaign
+  list-standards        Available standards and classes
+
+Options:
+  --standard <key>      Standard key, e.g. CISPR22
+  --class <class>       Class key, e.g. ClassB (optional when the standard has one class)
+  --interface <port>    Selection interface for standards with variants, e.g. ac_mains
+  --detector <det>      avg | qp | pk (check: default qp; stats: default avg)
+  --unit <unit>         Frequency unit of the measurement file (default Hz or from the header)
+  --from <Hz> --to <Hz> Mask range (default: the standard's coverage)
+  --ppd <n>             Mask points per decade (default 20)
+  --map <rules.json>    Batch rules [{pattern, standard, class, detector?, parameters?, frequency_unit?}];
+                        without it, --standard/--class/--detector/--unit apply to every file
+  --clean               Sort the trace by frequency and drop non-finite / non-positive rows (check, stats)
+  --json                Machine-readable JSON output; errors as {code, message}
+  --help                This text
+
+Exit status: 0 compliant, 1 limits exceeded, 2 usage or input error";
+
+// Options taking a value; --json, --clean and --help are flags
+const OPTIONS: [&str; 9] = ["standard", "class", "interface", "detector", "unit", "from", "to", "ppd", "map"];
+
+const EXIT_FAIL: u8 = 1;
+const EXIT_ERROR: u8 = 2;
+
+// Error behind exit status 2; usage errors are followed by the usage text
+#[derive(Debug, Serialize)]
+struct CliError {
+    code: &'static str,  // EmcError code, or USAGE / IO_ERROR
+    message: String,
+    #[serde(skip)]
+    usage: bool,
+}
+
+impl CliError {
+    fn usage(message: impl Into<String>) -> CliError {
+        CliError { code: "USAGE", message: message.into(), usage: true }
+    }
+
+    fn io(path: &str, error: std::io::Error) -> CliError {
+        CliError { code: "IO_ERROR", message: format!("{}: {}", path, error), usage: false }
+    }
+
+    // Prefix the message with the file it concerns
+    fn in_file(self, path: &str) -> CliError {
+        CliError { message: format!("{}: {}", path, self.message), ..self }
+    }
+}
+
+impl From<EmcError> for CliError {
+    fn from(error: EmcError) -> CliError {
+        CliError { code: error.code(), message: error.to_string(), usage: false }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Args {
+    command: String,
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+    json: bool,
+    clean: bool,
+    help: bool,
+}
+
+impl Args {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, CliError> {
+        let mut iter = args.into_iter();
+        let mut parsed = Args { command: iter.next().ok_or_else(|| CliError::usage("No command given"))?, ..Default::default() };
+
+        while let Some(arg) = iter.next() {
+            if arg == "--json" {
+                parsed.json = true;
+            } else if arg == "--clean" {
+                parsed.clean = true;
+            } else if arg == "--help" || arg == "-h" {
+                parsed.help = true;
+            } else if let Some(name) = arg.strip_prefix("--") {
+                let (name, value) = match name.split_once('=') {
+                    Some((name, value)) => (name.to_string(), value.to_string()),
+                    None => (name.to_string(), iter.next().ok_or_else(|| CliError::usage(format!("--{} needs a value", name)))?),
+                };
+                if !OPTIONS.contains(&name.as_str()) {
+                    return Err(CliError::usage(format!("Unknown option '--{}'", name)));
+                }
+                parsed.options.insert(name, value);
+            } else {
+                parsed.positional.push(arg);
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    fn required(&self, name: &str) -> Result<&str, CliError> {
+        self.option(name).ok_or_else(|| CliError::usage(format!("--{} is required", name)))
+    }
+
+    fn number(&self, name: &str) -> Result<Option<f64>, CliError> {
+        self.option(name)
+            .map(|v| v.parse::<f64>().map_err(|_| CliError::usage(format!("--{} must be a number, got '{}'", name, v))))
+            .transpose()
+    }
+
+    fn detector(&self, default: Detector) -> Result<Detector, CliError> {
+        let detector = self.option("detector").map(str::parse::<Detector>).transpose();
+        Ok(detector.map_err(|e| CliError { usage: true, ..e.into() })?.unwrap_or(default))
+    }
+
+    fn file(&self) -> Result<&str, CliError> {
+        self.positional.first().map(String::as_str).ok_or_else(|| CliError::usage(format!("'{}' needs a measurement file", self.command)))
+    }
+}
+
+fn verdict(passes: bool) -> ExitCode {
+    if passes { ExitCode::SUCCESS } else { ExitCode::from(EXIT_FAIL) }
+}
+
+fn load_standard(args: &Args) -> Result<EMCStandard, CliError> {
+    let key = args.required("standard")?;
+    let class = match args.option("class") {
+        Some(class) => class.to_string(),
+        None => {
+            let classes = EMCStandard::list_classes(key)?;
+            match classes.as_slice() {
+                [only] => only.clone(),
+                _ => return Err(CliError::usage(format!("--class is required for {}: one of {}", key, classes.join(", ")))),
+            }
+        }
+    };
+    let parameters = SelectionParameters { interface: args.option("interface").map(str::to_string), ..Default::default() };
+    Ok(EMCStandard::get_standard_with(key, &class, &parameters)?)
+}
+
+fn load_measurement(args: &Args) -> Result<Measurement, CliError> {
+    let path = args.file()?;
+    let text = std::fs::read_to_string(path).map_err(|e| CliError::io(path, e))?;
+    parse_measurement(&text, args.option("unit")).map_err(|e| CliError::from(e).in_file(path))
+}
+
+// Detector trace of the measurement, validated (or cleaned with --clean, listing what was changed)
+fn load_trace(args: &Args, measurement: &Measurement, detector: Detector) -> Result<ValidatedTrace, CliError> {
+    let options = if args.clean { ValidationOptions::lenient() } else { ValidationOptions::default() };
+    let trace = validate_trace(&measurement.frequencies, measurement.trace(detector)?, &options).map_err(|e| {
+        let error = CliError::from(e).in_file(args.file().unwrap_or_default());
+        CliError { message: format!("{} (--clean sorts the trace and drops invalid rows)", error.message), ..error }
+    })?;
+    for d in &trace.diagnostics {
+        eprintln!("warning: point {} ({} Hz, {}): {}", d.index, d.frequency, d.amplitude, d.issue);
+    }
+    Ok(trace)
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), CliError> {
+    println!("{}", serde_json::to_string_pretty(value).map_err(|e| EmcError::invalid(e.to_string()))?);
+    Ok(())
+}
+
+fn format_frequency(frequency: f64) -> String {
+    match frequency {
+        f if f >= 1e9 => format!("{:.4} GHz", f / 1e9),
+        f if f >= 1e6 => format!("{:.4} MHz", f / 1e6),
+        f if f >= 1e3 => format!("{:.3} kHz", f / 1e3),
+        f => format!("{:.1} Hz", f),
+    }
+}
+
+#[derive(Serialize)]
+struct CheckReport<'a> {
+    file: &'a str,
+    standard: &'a str,
+    detector: Detector,
+    #[serde(flatten)]
+    summary: ComplianceSummary,
+    failures: Vec<ComplianceResult>,
+}
+
+fn check(args: &Args) -> Result<ExitCode, CliError> {
+    let standard = load_standard(args)?;
+    let measurement = load_measurement(args)?;
+    let detector = args.detector(Detector::Qp)?;
+
+    let trace = load_trace(args, &measurement, detector)?;
+    // Same as batch: a trace outside the standard's range is an input error, not a pass
+    if !standard.covers_any(&trace.frequencies) {
+        return Err(EmcError::invalid(format!("No measured point lies within the frequency range of {} (check --unit)", standard.name)).into());
+    }
+    let results = standard.check_compliance(&trace.frequencies, &trace.amplitudes, detector)?;
+    let summary = ComplianceSummary::from_results(&results);
+    let passes = summary.passes;
+
+    if args.json {
+        let failures = results.into_iter().filter(|r| !r.passes).collect();
+        print_json(&CheckReport { file: args.file()?, standard: &standard.name, detector, summary, failures })?;
+    } else {
+        println!("{} ({}), {}", standard.name, detector.as_str().to_uppercase(), args.file()?);
+        println!("  points: {} ({} with a limit), violations: {}", summary.points, summary.points_with_limit, summary.violations);
+        if !summary.worst_margin.is_nan() {
+            println!("  worst margin: {:.2} dB at {}", summary.worst_margin, format_frequency(summary.worst_frequency));
+        }
+        println!("{}", if passes { "PASS" } else { "FAIL" });
+    }
+    Ok(verdict(passes))
+}
+
+fn stats(args: &Args) -> Result<ExitCode, CliError> {
+    let standard = load_standard(args)?;
+    let measurement = load_measurement(args)?;
+    let detector = args.detector(Detector::Avg)?;
+    let trace = load_trace(args, &measurement, detector)?;
+    let stats = standard.analyze_detector_statistics(&trace.frequencies, &trace.amplitudes, detector)?;
+
+    if args.json {
+        print_json(&stats)?;
+    } else {
+        println!("{} ({} trace), {}", standard.name, stats.detector.as_str().to_uppercase(), args.file()?);
+        println!("  mean {:.2}, std {:.2}, min {:.2}, max {:.2}", stats.mean_amplitude, stats.std_amplitude, stats.min_amplitude, stats.max_amplitude);
+        println!("  p95 {:.2}, p99 {:.2}", stats.percentile_95, stats.percentile_99);
+        // The trace's own detector decides the exit status; the others are for comparison
+        for d in &stats.detectors {
+            let marker = if d.detector == stats.detector { '*' } else { ' ' };
+            let summary = &d.summary;
+            print!("{} {} limit: {} violations ({:.1} % compliant)", marker, d.detector.as_str().to_uppercase(), summary.violations, d.compliance_rate);
+            if summary.worst_margin.is_nan() {
+                println!(", no limit");
+            } else {
+                println!(", worst margin {:.2} dB at {}", summary.worst_margin, format_frequency(summary.worst_frequency));
+            }
+        }
+    }
+    Ok(verdict(stats.violation_count == 0))
+}
+
+fn mask(args: &Args) -> Result<ExitCode, CliError> {
+    let standard = load_standard(args)?;
+    let (lo, hi) = standard.frequency_span();
+    let f_min = args.number("from")?.unwrap_or(lo);
+    let f_max = args.number("to")?.unwrap_or(hi);
+    let ppd = args.number("ppd")?.unwrap_or(20.0);
+    if !(f_min > 0.0 && f_max > f_min) || ppd < 1.0 {
+        return Err(CliError::usage("Mask range must satisfy 0 < --from < --to, with --ppd >= 1"));
+    }
+
+    let mask = standard.generate_mask(f_min, f_max, ppd as usize);
+    if args.json {
+        print_json(&mask)?;
+    } else {
+        println!("detector,frequency_hz,limit");
+        for (detector, points) in [("avg", &mask.avg), ("qp", &mask.qp), ("pk", &mask.pk)] {
+            for point in points {
+                println!("{},{},{:.2}", detector, point.frequency, point.amplitude);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn batch_rules(args: &Args) -> Result<Vec<BatchRule>, CliError> {
+    if let Some(path) = args.option("map") {
+        let text = std::fs::read_to_string(path).map_err(|e| CliError::io(path, e))?;
+        return serde_json::from_str(&text).map_err(|e| CliError::from(EmcError::from(e)).in_file(path));
+    }
+    Ok(vec![BatchRule {
+        pattern: "*".to_string(),
+        standard: args.required("standard")?.to_string(),
+        class: args.required("class")?.to_string(),
+        detector: args.detector(Detector::Qp)?,
+        parameters: SelectionParameters { interface: args.option("interface").map(str::to_string), ..Default::default() },
+        frequency_unit: args.option("unit").map(str::to_string),
+    }])
+}
+
+// Exit status: input errors take precedence over failures, unmapped files are only reported
+fn batch(args: &Args) -> Result<ExitCode, CliError> {
+    let target = args.positional.first().ok_or_else(|| CliError::usage("'batch' needs a directory or file pattern"))?;
+    let files = collect_files(target)?;
+    if files.is_empty() {
+        return Err(EmcError::invalid(format!("No files match '{}'", target)).into());
+    }
+
+    let mut runner = BatchRunner::new(batch_rules(args)?)?;
+    let results = files
+        .iter()
+        .map(|path| {
+            let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            match std::fs::read_to_string(path) {
+                Ok(text) => runner.check(&name, &text),
+                Err(e) => FileResult::error(&name, runner.rule_for(&name), e.to_string()),
+            }
+        })
+        .collect();
+    let report = campaign_report(results);
+    let stats = &report.statistics;
+
+    if args.json {
+        print_json(&report)?;
+    } else {
+        println!("{:<40} {:<28} {:>12} {:>14}  VERDICT", "FILE", "STANDARD", "MARGIN (dB)", "FREQUENCY");
+        for file in &report.files {
+            let (margin, frequency) = match &file.summary {
+                Some(s) if !s.worst_margin.is_nan() => (format!("{:.2}", s.worst_margin), format_frequency(s.worst_frequency)),
+                _ => ("-".to_string(), "-".to_string()),
+            };
+            println!(
+                "{:<40} {:<28} {:>12} {:>14}  {:?}{}",
+                file.file,
+                file.standard.as_deref().unwrap_or("-"),
+                margin,
+                frequency,
+                file.verdict,
+                file.error.as_deref().map(|e| format!(": {}", e)).unwrap_or_default()
+            );
+        }
+        println!();
+        println!(
+            "{} files: {} passed, {} failed, {} errors, {} unmapped ({:.1} % pass rate)",
+            stats.files, stats.passed, stats.failed, stats.errors, stats.unmapped, stats.pass_rate
+        );
+        if let Some(worst) = &stats.worst_file {
+            println!("worst margin {:.2} dB at {} in {}", stats.worst_margin, format_frequency(stats.worst_frequency), worst);
+            println!("mean worst margin {:.2} dB, {} violating points", stats.mean_worst_margin, stats.total_violations);
+        }
+        for tally in &stats.by_standard {
+            println!("  {}: {}/{} passed, worst {:.2} dB", tally.standard, tally.passed, tally.files, tally.worst_margin);
+        }
+    }
+
+    if stats.errors > 0 {
+        // With --json the per-file errors are in the report
+        if !args.json {
+            eprintln!("error: {} of {} files could not be checked", stats.errors, stats.files);
+        }
+        return Ok(ExitCode::from(EXIT_ERROR));
+    }
+    Ok(verdict(report.files.iter().all(|f| f.verdict != Verdict::Fail)))
+}
+
+fn list_standards(args: &Args) -> Result<ExitCode, CliError> {
+    let entries = catalog::catalog()?;
+    if args.json {
+        print_json(&entries)?;
+    } else {
+        for entry in &entries {
+            let variant = entry.selector.as_ref().and_then(|s| s.interface.as_deref()).map(|i| format!(" [{}]", i)).unwrap_or_default();
+            println!("{:<28} {:<10} {}{}", entry.standard, entry.class, entry.name, variant);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run(args: &Args) -> Result<ExitCode, CliError> {
+    if args.help {
+        println!("{}", USAGE);
+        return Ok(ExitCode::SUCCESS);
+    }
+    match args.command.as_str() {
+        "check" => check(args),
+        "stats" => stats(args),
+        "mask" => mask(args),
+        "batch" => batch(args),
+        "list-standards" => list_standards(args),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(ExitCode::SUCCESS)
+        }
+        other => Err(CliError::usage(format!("Unknown command '{}'", other))),
+    }
+}
+
+fn main() -> ExitCode {
+    let raw: Vec<String> = std::env::args().skip(1).collect();
+    // Known before parsing, so that argument errors are JSON too
+    let json = raw.iter().any(|arg| arg == "--json");
+
+    match Args::parse(raw).and_then(|args| run(&args)) {
+        Ok(code) => code,
+        Err(e) if json => {
+            println!("{}", serde_json::to_string_pretty(&e).unwrap_or_default());
+            ExitCode::from(EXIT_ERROR)
+        }
+        Err(e) if e.usage => {
+            eprintln!("error: {}\n\n{}", e.message, USAGE);
+            ExitCode::from(EXIT_ERROR)
+        }
+        Err(e) => {
+            eprintln!("error: {}", e.message);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Args {
+        Args::parse(line.split_whitespace().map(str::to_string)).unwrap()
+    }
+
+    #[test]
+    fn test_argument_parsing() {
+        let parsed = args("check scan.csv --standard CISPR22 --class=ClassB --json --detector avg");
+        assert_eq!(parsed.command, "check");
+        assert_eq!(parsed.file().unwrap(), "scan.csv");
+        assert_eq!(parsed.option("class"), Some("ClassB"));
+        assert_eq!(parsed.option("detector"), Some("avg"));
+        assert!(parsed.json);
+
+        assert!(Args::parse(vec!["mask".to_string(), "--from".to_string()]).is_err());
+        let typo = Args::parse("check scan.csv --detecter pk".split_whitespace().map(str::to_string)).unwrap_err();
+        assert!(typo.usage && typo.message.contains("--detecter"));
+        assert!(args("mask --ppd ten").number("ppd").is_err());
+        assert!(args("stats").file().is_err());
+        assert_eq!(parsed.detector(Detector::Qp).unwrap(), Detector::Avg);
+        assert_eq!(args("check --detector rms").detector(Detector::Qp).unwrap_err().code, "INVALID_DETECTOR");
+
+        // --help is a flag on any command
+        let help = args("check --help");
+        assert!(help.help && run(&help).is_ok());
+    }
+
+    #[test]
+    fn test_standard_selection() {
+        assert_eq!(load_standard(&args("mask --standard CISPR22 --class ClassB")).unwrap().name, "CISPR 22 Class B");
+        // Ambiguous class and missing standard are usage errors
+        assert!(load_standard(&args("mask --standard CISPR22")).unwrap_err().message.contains("ClassA"));
+        assert!(load_standard(&args("mask")).unwrap_err().usage);
+
+        // Lookup failures keep their code and are not usage errors
+        let error = load_standard(&args("mask --standard CISPR99 --class ClassB")).unwrap_err();
+        assert_eq!((error.code, error.usage), ("STANDARD_NOT_FOUND", false));
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json, serde_json::json!({ "code": "STANDARD_NOT_FOUND", "message": "Standard 'CISPR99' not found" }));
+    }
+
+    #[test]
+    fn test_check_outside_range_is_an_error() {
+        // 0.15 / 1 read as Hz: below CISPR 22's 150 kHz
+        let path = std::env::temp_dir().join(format!("emc_check_{}.csv", std::process::id()));
+        std::fs::write(&path, "0.15,50\n1,40\n").unwrap();
+        let line = format!("check {} --standard CISPR22 --class ClassB", path.display());
+        assert!(check(&args(&line)).unwrap_err().message.contains("--unit"));
+        assert!(check(&args(&format!("{} --unit MHz", line))).is_ok());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
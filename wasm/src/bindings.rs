@@ -0,0 +1,526 @@
+// wasm/src/bindings.rs
+// JavaScript bindings: JSON / JsValue wrappers over the native API
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::*;
+
+#[wasm_bindgen]
+extern "C" {
+    fn alert(s: &str);
+    
+    #[wasm_bindgen(js_namespace = console)]
+    fn log(s: &str);
+}
+
+macro_rules! console_log {
+    ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
+}
+
+// Errors reach JavaScript as plain objects: { code, message, details }
+#[derive(Serialize)]
+struct JsError<'a> {
+    #[serde(flatten)]
+    error: &'a EmcError,
+    message: String,
+}
+
+impl From<EmcError> for JsValue {
+    fn from(error: EmcError) -> JsValue {
+        let js_error = JsError { error: &error, message: error.to_string() };
+        js_error
+            .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+            .unwrap_or_else(|_| JsValue::from_str(&error.to_string()))
+    }
+}
+
+#[wasm_bindgen(start)]
+pub fn init() {
+    console_error_panic_hook::set_once();
+}
+
+#[wasm_bindgen]
+pub fn get_emc_standard(
+    standard_name: &str,
+    emc_class: &str,
+    interface: Option<String>,
+    parameters_json: Option<String>,
+) -> Result<JsValue, JsValue> {
+    console_log!("Getting EMC standard: {} {}", standard_name, emc_class);
+
+    let mut parameters: SelectionParameters = match parameters_json {
+        Some(json) => serde_json::from_str(&json).map_err(EmcError::from)?,
+        None => SelectionParameters::default(),
+    };
+    if interface.is_some() {
+        parameters.interface = interface;
+    }
+    
+    match EMCStandard::get_standard_with(standard_name, emc_class, &parameters) {
+        Ok(standard) => {
+            console_log!("Standard loaded successfully: {}", standard.name);
+            serde_wasm_bindgen::to_value(&standard).map_err(|e| JsValue::from_str(&e.to_string()))
+        },
+        Err(e) => {
+            console_log!("Error loading standard: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn query_emc_standards(query_json: Option<String>) -> Result<JsValue, JsValue> {
+    let query: catalog::StandardQuery = match query_json {
+        Some(json) => serde_json::from_str(&json).map_err(EmcError::from)?,
+        None => catalog::StandardQuery::default(),
+    };
+    let standards = catalog::query_standards(&query)?;
+    serde_wasm_bindgen::to_value(&standards).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+// Available values per metadata field among the standards matching the query
+#[wasm_bindgen]
+pub fn emc_standard_facets(query_json: Option<String>) -> Result<JsValue, JsValue> {
+    let query: catalog::StandardQuery = match query_json {
+        Some(json) => serde_json::from_str(&json).map_err(EmcError::from)?,
+        None => catalog::StandardQuery::default(),
+    };
+    let standards = catalog::query_standards(&query)?;
+    serde_wasm_bindgen::to_value(&catalog::facets(&standards)).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn list_standard_editions(family: &str) -> Result<JsValue, JsValue> {
+    let editions = editions::list_editions(family)?;
+    serde_wasm_bindgen::to_value(&editions).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn get_emc_standard_edition(family: &str, edition: &str, emc_class: &str, filter: Option<String>) -> Result<JsValue, JsValue> {
+    let standard = editions::get_standard_edition(family, edition, emc_class, filter.as_deref())?;
+    serde_wasm_bindgen::to_value(&standard).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn diff_emc_limits(from_json: &str, to_json: &str, points_per_decade: usize) -> Result<JsValue, JsValue> {
+    let from = EMCStandard::from_json(from_json)?;
+    let to = EMCStandard::from_json(to_json)?;
+
+    let diff = editions::diff_limits(&from, &to, points_per_decade)?;
+    serde_wasm_bindgen::to_value(&diff).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+// Tightest-of envelope; members_json is an array of {standard, distance?}
+#[wasm_bindgen]
+pub fn composite_emc_limits(
+    name: &str,
+    members_json: &str,
+    target_distance: Option<f64>,
+    points_per_decade: usize,
+) -> Result<JsValue, JsValue> {
+    let members: Vec<composite::CompositeMember> = serde_json::from_str(members_json).map_err(EmcError::from)?;
+
+    let composite = composite::composite_limits(name, &members, target_distance, points_per_decade)?;
+    serde_wasm_bindgen::to_value(&composite).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+// Offset over [f_start, f_stop], or the whole span when no range is given
+#[wasm_bindgen]
+pub fn offset_emc_limits(
+    standard_json: &str,
+    name: &str,
+    offset: f64,
+    f_start: Option<f64>,
+    f_stop: Option<f64>,
+) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+    let range = match (f_start, f_stop) {
+        (None, None) => None,
+        (start, stop) => Some((start.unwrap_or(0.0), stop.unwrap_or(f64::INFINITY))),
+    };
+
+    let result = limit_ops::offset_limits(&standard, name, offset, range)?;
+    serde_wasm_bindgen::to_value(&result).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn truncate_emc_limits(standard_json: &str, name: &str, f_min: f64, f_max: f64) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+
+    let result = limit_ops::truncate_limits(&standard, name, f_min, f_max)?;
+    serde_wasm_bindgen::to_value(&result).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn splice_emc_limits(low_json: &str, high_json: &str, name: &str, breakpoint: f64) -> Result<JsValue, JsValue> {
+    let low = EMCStandard::from_json(low_json)?;
+    let high = EMCStandard::from_json(high_json)?;
+
+    let result = limit_ops::splice_limits(&low, &high, name, breakpoint)?;
+    serde_wasm_bindgen::to_value(&result).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+// Standards JSON document for a (derived) standard, loadable like emc_standards.json
+#[wasm_bindgen]
+pub fn emc_standard_to_json(standard_json: &str, standard_key: &str, class_key: &str) -> Result<String, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+    limit_ops::to_standards_json(&standard, standard_key, class_key).map_err(JsValue::from)
+}
+
+// Import a CSV / receiver limit line; options_json is {format, name, detector?, interpolation?, frequency_unit?}
+#[wasm_bindgen]
+pub fn import_limit_line(text: &str, options_json: &str) -> Result<JsValue, JsValue> {
+    let options: limit_lines::ImportOptions = serde_json::from_str(options_json).map_err(EmcError::from)?;
+
+    let imported = limit_lines::import_limit_line(text, &options)?;
+    serde_wasm_bindgen::to_value(&imported).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn export_limit_line(standard_json: &str, format: &str, detector: Option<String>) -> Result<String, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+    let format = limit_lines::LimitLineFormat::parse(format)?;
+
+    let detector = detector.as_deref().map(str::parse::<Detector>).transpose()?;
+
+    Ok(limit_lines::export_limit_line(&standard, format, detector))
+}
+
+// Per-harmonic rms currents (index 0 = fundamental, up to the 40th) of a sampled mains current
+#[wasm_bindgen]
+pub fn harmonic_currents_from_waveform(samples: &[f64], sample_rate: f64, fundamental_frequency: f64) -> Result<Vec<f64>, JsValue> {
+    harmonic_current::harmonics_from_waveform(samples, sample_rate, fundamental_frequency).map_err(JsValue::from)
+}
+
+#[wasm_bindgen]
+pub fn check_harmonic_currents_61000_3_2(
+    harmonics: &[f64],
+    equipment_class: &str,
+    active_power: Option<f64>,
+    power_factor: Option<f64>,
+) -> Result<JsValue, JsValue> {
+    let class = harmonic_current::EquipmentClass::parse(equipment_class)?;
+
+    let report = harmonic_current::check_iec61000_3_2(harmonics, class, active_power, power_factor)?;
+    serde_wasm_bindgen::to_value(&report).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn check_harmonic_currents_61000_3_12(
+    harmonics: &[f64],
+    rsce: f64,
+    balanced_three_phase: bool,
+    reference_current: Option<f64>,
+) -> Result<JsValue, JsValue> {
+    let report = harmonic_current::check_iec61000_3_12(harmonics, rsce, balanced_three_phase, reference_current)?;
+    serde_wasm_bindgen::to_value(&report).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+// IEC 61000-3-3 flicker evaluation; options_json is {nominal_voltage?, mains_frequency?, dmax_limit?}
+#[wasm_bindgen]
+pub fn evaluate_voltage_flicker(half_cycle_rms: &[f64], options_json: Option<String>) -> Result<JsValue, JsValue> {
+    let options: flicker::FlickerOptions = match options_json {
+        Some(json) => serde_json::from_str(&json).map_err(EmcError::from)?,
+        None => flicker::FlickerOptions::default(),
+    };
+
+    let report = flicker::evaluate_flicker(half_cycle_rms, &options)?;
+    serde_wasm_bindgen::to_value(&report).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+// Checks a measured trace before analysis; options {sort, drop_invalid} clean it up instead of rejecting it
+#[wasm_bindgen]
+pub fn validate_measurement(frequencies: &[f64], amplitudes: &[f64], options_json: Option<String>) -> Result<JsValue, JsValue> {
+    let options: validation::ValidationOptions = match options_json {
+        Some(json) => serde_json::from_str(&json).map_err(EmcError::from)?,
+        None => validation::ValidationOptions::default(),
+    };
+    let trace = validation::validate_trace(frequencies, amplitudes, &options)?;
+    serde_wasm_bindgen::to_value(&trace).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn list_standard_variants(standard_name: &str, emc_class: &str) -> Result<JsValue, JsValue> {
+    let variants = EMCStandard::list_variants(standard_name, emc_class)?;
+    serde_wasm_bindgen::to_value(&variants).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn list_available_standards() -> Result<JsValue, JsValue> {
+    match EMCStandard::list_standards() {
+        Ok(standards) => serde_wasm_bindgen::to_value(&standards).map_err(|e| JsValue::from_str(&e.to_string())),
+        Err(e) => Err(e.into())
+    }
+}
+
+#[wasm_bindgen]
+pub fn list_standard_classes(standard_name: &str) -> Result<JsValue, JsValue> {
+    match EMCStandard::list_classes(standard_name) {
+        Ok(classes) => serde_wasm_bindgen::to_value(&classes).map_err(|e| JsValue::from_str(&e.to_string())),
+        Err(e) => Err(e.into())
+    }
+}
+
+#[wasm_bindgen]
+pub fn calculate_emc_limit(standard_json: &str, frequency: f64) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+
+    let result = standard.interp_log(frequency);
+    serde_wasm_bindgen::to_value(&result).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn check_emc_compliance(
+    standard_json: &str,
+    frequencies: &[f64],
+    amplitudes: &[f64],
+    measurement_type: &str
+) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+    let detector: Detector = measurement_type.parse()?;
+
+    let results = standard.check_compliance(frequencies, amplitudes, detector)?;
+
+    serde_wasm_bindgen::to_value(&results).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn generate_emc_mask(
+    standard_json: &str,
+    f_min: f64,
+    f_max: f64,
+    points_per_decade: usize
+) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+
+    let mask = standard.generate_mask(f_min, f_max, points_per_decade);
+    serde_wasm_bindgen::to_value(&mask).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+// Enhanced WASM bindings for statistical analysis; measurement_type is the trace's detector (default avg)
+#[wasm_bindgen]
+pub fn analyze_emc_statistics(
+    standard_json: &str,
+    frequencies: &[f64],
+    amplitudes: &[f64],
+    measurement_type: Option<String>
+) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+
+    let detector = measurement_type.as_deref().map(str::parse).transpose()?.unwrap_or(Detector::Avg);
+    let stats = standard.analyze_detector_statistics(frequencies, amplitudes, detector)?;
+    serde_wasm_bindgen::to_value(&stats).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn generate_adaptive_emc_mask(
+    standard_json: &str,
+    f_min: f64,
+    f_max: f64,
+    target_points: usize
+) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+
+    let mask = standard.generate_adaptive_mask(f_min, f_max, target_points);
+    serde_wasm_bindgen::to_value(&mask).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn plan_emc_scan(
+    standard_json: &str,
+    f_min: f64,
+    f_max: f64
+) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+
+    let plan = scan_plan::plan_scan(&standard, f_min, f_max);
+    serde_wasm_bindgen::to_value(&plan).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn time_domain_to_spectrum(
+    standard_json: &str,
+    sample_rate: f64,
+    samples: &[f64],
+    window: &str
+) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+    let window = time_domain::WindowType::parse(window)?;
+
+    let spectrum = time_domain::capture_to_spectrum(&standard, sample_rate, samples, window)?;
+    serde_wasm_bindgen::to_value(&spectrum).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn emulate_detectors_envelope(
+    envelope: &[f64],
+    sample_rate: f64,
+    frequency: f64
+) -> Result<JsValue, JsValue> {
+    let readings = detectors::detect_envelope(envelope, sample_rate, frequency)?;
+    serde_wasm_bindgen::to_value(&readings).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn emulate_detectors(
+    samples: &[f64],
+    sample_rate: f64,
+    frequencies: &[f64]
+) -> Result<JsValue, JsValue> {
+    let readings = detectors::detect_capture(samples, sample_rate, frequencies)?;
+    serde_wasm_bindgen::to_value(&readings).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn analyze_switching_harmonics(
+    standard_json: &str,
+    frequencies: &[f64],
+    amplitudes: &[f64],
+    measurement_type: &str,
+    fundamental: Option<f64>
+) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+    let detector: Detector = measurement_type.parse()?;
+
+    let analysis = harmonics::analyze_harmonics(&standard, frequencies, amplitudes, detector, fundamental)?;
+    serde_wasm_bindgen::to_value(&analysis).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn estimate_switching_frequency(
+    frequencies: &[f64],
+    amplitudes: &[f64]
+) -> Result<JsValue, JsValue> {
+    let estimate = harmonics::estimate_fundamental(frequencies, amplitudes)
+        .ok_or_else(|| EmcError::invalid("Could not estimate the switching frequency from the trace"))?;
+    serde_wasm_bindgen::to_value(&estimate).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn calculate_filter_requirement(
+    standard_json: &str,
+    frequencies: &[f64],
+    amplitudes: &[f64],
+    measurement_type: &str,
+    margin: f64
+) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+    let detector: Detector = measurement_type.parse()?;
+
+    let requirement = filter_design::filter_requirement(&standard, frequencies, amplitudes, detector, margin)?;
+    serde_wasm_bindgen::to_value(&requirement).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn separate_cm_dm(
+    standard_json: &str,
+    frequencies: &[f64],
+    line_dbuv: &[f64],
+    line_phase_deg: &[f64],
+    neutral_dbuv: &[f64],
+    neutral_phase_deg: &[f64],
+    measurement_type: &str
+) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+    let detector: Detector = measurement_type.parse()?;
+
+    let spectra = noise_modes::separate_from_lines(frequencies, line_dbuv, line_phase_deg, neutral_dbuv, neutral_phase_deg)?;
+    let report = noise_modes::check_modes(&standard, spectra, detector)?;
+    serde_wasm_bindgen::to_value(&report).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn check_separator_cm_dm(
+    standard_json: &str,
+    frequencies: &[f64],
+    cm_output: &[f64],
+    dm_output: &[f64],
+    cm_correction: f64,
+    dm_correction: f64,
+    measurement_type: &str
+) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+    let detector: Detector = measurement_type.parse()?;
+
+    let spectra = noise_modes::separate_from_separator(frequencies, cm_output, dm_output, cm_correction, dm_correction)?;
+    let report = noise_modes::check_modes(&standard, spectra, detector)?;
+    serde_wasm_bindgen::to_value(&report).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn apply_transducer(
+    transducer_json: &str,
+    frequencies: &[f64],
+    amplitudes: &[f64]
+) -> Result<JsValue, JsValue> {
+    let transducer: transducers::TransducerTable = serde_json::from_str(transducer_json).map_err(EmcError::from)?;
+
+    let corrected = transducer.apply(frequencies, amplitudes)?;
+    serde_wasm_bindgen::to_value(&corrected).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn lisn_impedance_table(
+    model: &str,
+    f_min: f64,
+    f_max: f64,
+    points_per_decade: usize
+) -> Result<JsValue, JsValue> {
+    let lisn = lisn::LisnModel::parse(model)?;
+
+    let table = lisn.impedance_table(f_min, f_max, points_per_decade);
+    serde_wasm_bindgen::to_value(&table).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn lisn_vdf_transducer(
+    model: &str,
+    f_min: f64,
+    f_max: f64,
+    points_per_decade: usize
+) -> Result<JsValue, JsValue> {
+    let lisn = lisn::LisnModel::parse(model)?;
+
+    let transducer = lisn.vdf_transducer(f_min, f_max, points_per_decade);
+    serde_wasm_bindgen::to_value(&transducer).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn lisn_current_to_voltage(
+    model: &str,
+    frequencies: &[f64],
+    current_dbua: &[f64]
+) -> Result<JsValue, JsValue> {
+    let lisn = lisn::LisnModel::parse(model)?;
+
+    let voltages = lisn.current_to_voltage(frequencies, current_dbua)?;
+    serde_wasm_bindgen::to_value(&voltages).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn probe_voltage_to_current(
+    probe_json: &str,
+    frequencies: &[f64],
+    probe_dbuv: &[f64]
+) -> Result<JsValue, JsValue> {
+    let probe: current_probe::CurrentProbe = serde_json::from_str(probe_json).map_err(EmcError::from)?;
+
+    let current = probe.voltage_to_current(frequencies, probe_dbuv)?;
+    serde_wasm_bindgen::to_value(&current).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn check_current_probe_compliance(
+    standard_json: &str,
+    probe_json: &str,
+    frequencies: &[f64],
+    probe_dbuv: &[f64],
+    measurement_type: &str
+) -> Result<JsValue, JsValue> {
+    let standard = EMCStandard::from_json(standard_json)?;
+    let detector: Detector = measurement_type.parse()?;
+    let probe: current_probe::CurrentProbe = serde_json::from_str(probe_json).map_err(EmcError::from)?;
+
+    let results = current_probe::check_current_compliance(&standard, &probe, frequencies, probe_dbuv, detector)?;
+    serde_wasm_bindgen::to_value(&results).map_err(|e| JsValue::from_str(&e.to_string()))
+}
@@ -0,0 +1,219 @@
+// wasm/src/catalog.rs
+// Standard metadata (type, port, distance, class...) and faceted queries over the embedded standards
+use serde::{Deserialize, Serialize};
+
+use crate::{EMCStandard, EmcError, LimitUnit, StandardClass, VariantSelector};
+
+// Descriptive metadata, same vocabulary as the public standards database
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StandardMetadata {
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub standard_type: Option<String>,     // "conducted" | "radiated"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subtype: Option<String>,           // e.g. "mains_ports", "free_space"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub measurement_type: Option<String>,  // "voltage" | "current" | "field_strength"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance: Option<String>,          // e.g. "10m"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power_range: Option<String>,       // e.g. "≤20kVA", "any"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,             // e.g. "A", "B", "C2"
+}
+
+impl StandardMetadata {
+    // Fields set on `other` take precedence (variant metadata over class metadata)
+    pub fn merged(&self, other: &StandardMetadata) -> StandardMetadata {
+        StandardMetadata {
+            standard_type: other.standard_type.clone().or_else(|| self.standard_type.clone()),
+            subtype: other.subtype.clone().or_else(|| self.subtype.clone()),
+            measurement_type: other.measurement_type.clone().or_else(|| self.measurement_type.clone()),
+            distance: other.distance.clone().or_else(|| self.distance.clone()),
+            power_range: other.power_range.clone().or_else(|| self.power_range.clone()),
+            class: other.class.clone().or_else(|| self.class.clone()),
+        }
+    }
+}
+
+// Filter over metadata and frequency coverage; unset fields match anything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StandardQuery {
+    #[serde(default, flatten)]
+    pub metadata: StandardMetadata,
+    #[serde(default)]
+    pub f_min: Option<f64>,  // Hz, coverage must overlap [f_min, f_max]
+    #[serde(default)]
+    pub f_max: Option<f64>,
+}
+
+// One selectable limit set (class, or class variant) with its metadata and coverage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandardInfo {
+    pub standard: String,  // key for get_standard
+    pub class: String,     // class key for get_standard
+    pub name: String,
+    pub description: String,
+    pub unit: LimitUnit,
+    pub metadata: StandardMetadata,
+    pub selector: Option<VariantSelector>,  // selection parameters needed for this variant
+    pub f_min: f64,  // Hz
+    pub f_max: f64,  // Hz
+}
+
+// Distinct values per metadata field among a set of entries
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StandardFacets {
+    #[serde(rename = "type")]
+    pub standard_type: Vec<String>,
+    pub subtype: Vec<String>,
+    pub measurement_type: Vec<String>,
+    pub distance: Vec<String>,
+    pub power_range: Vec<String>,
+    pub class: Vec<String>,
+}
+
+impl StandardQuery {
+    pub fn matches(&self, info: &StandardInfo) -> bool {
+        let field = |wanted: &Option<String>, value: &Option<String>| match (wanted, value) {
+            (None, _) => true,
+            (Some(w), Some(v)) => w.eq_ignore_ascii_case(v),
+            (Some(_), None) => false,
+        };
+        let q = &self.metadata;
+        let m = &info.metadata;
+
+        field(&q.standard_type, &m.standard_type)
+            && field(&q.subtype, &m.subtype)
+            && field(&q.measurement_type, &m.measurement_type)
+            && field(&q.distance, &m.distance)
+            && field(&q.power_range, &m.power_range)
+            && field(&q.class, &m.class)
+            && self.f_max.is_none_or(|f| info.f_min <= f)
+            && self.f_min.is_none_or(|f| info.f_max >= f)
+    }
+}
+
+fn describe(standard: &str, class_key: &str, class: &StandardClass, metadata: StandardMetadata, selector: Option<VariantSelector>) -> StandardInfo {
+    let (f_min, f_max) = EMCStandard::from_standard_class(class).frequency_span();
+    StandardInfo {
+        standard: standard.to_string(),
+        class: class_key.to_string(),
+        name: class.name.clone(),
+        description: class.description.clone(),
+        unit: class.unit,
+        metadata,
+        selector,
+        f_min,
+        f_max,
+    }
+}
+
+// Every class and class variant, sorted by standard and class key
+pub fn catalog() -> Result<Vec<StandardInfo>, EmcError> {
+    let data = EMCStandard::load_standards_data()?;
+    let mut entries = Vec::new();
+
+    for (standard, classes) in &data.standards {
+        for (class_key, class) in classes {
+            let class_metadata = class.metadata.clone().unwrap_or_default();
+            if class.has_limits() || class.variants.is_none() {
+                entries.push(describe(standard, class_key, class, class_metadata.clone(), None));
+            }
+            for variant in class.variants.iter().flatten() {
+                let metadata = class_metadata.merged(&variant.class.metadata.clone().unwrap_or_default());
+                entries.push(describe(standard, class_key, &variant.class, metadata, Some(variant.selector.clone())));
+            }
+        }
+    }
+
+    entries.sort_by(|a, b| (&a.standard, &a.class, &a.name).cmp(&(&b.standard, &b.class, &b.name)));
+    Ok(entries)
+}
+
+pub fn query_standards(query: &StandardQuery) -> Result<Vec<StandardInfo>, EmcError> {
+    Ok(catalog()?.into_iter().filter(|info| query.matches(info)).collect())
+}
+
+pub fn facets(entries: &[StandardInfo]) -> StandardFacets {
+    let values = |get: fn(&StandardMetadata) -> &Option<String>| {
+        let mut v: Vec<String> = entries.iter().filter_map(|e| get(&e.metadata).clone()).collect();
+        v.sort();
+        v.dedup();
+        v
+    };
+
+    StandardFacets {
+        standard_type: values(|m| &m.standard_type),
+        subtype: values(|m| &m.subtype),
+        measurement_type: values(|m| &m.measurement_type),
+        distance: values(|m| &m.distance),
+        power_range: values(|m| &m.power_range),
+        class: values(|m| &m.class),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(standard_type: &str, subtype: &str, class: &str) -> StandardQuery {
+        StandardQuery {
+            metadata: StandardMetadata {
+                standard_type: Some(standard_type.to_string()),
+                subtype: Some(subtype.to_string()),
+                class: Some(class.to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_conducted_mains_class_b() {
+        let results = query_standards(&query("conducted", "mains_ports", "B")).unwrap();
+
+        assert!(results.iter().any(|r| r.standard == "CISPR22" && r.class == "ClassB"));
+        assert!(results.iter().any(|r| r.standard == "CISPR11_Group1" && r.selector.is_some()));
+        assert!(results.iter().all(|r| r.metadata.class.as_deref() == Some("B")));
+        assert!(results.iter().all(|r| r.f_min <= 150e3 && r.f_max >= 30e6));
+    }
+
+    #[test]
+    fn test_variant_metadata_and_frequency_filter() {
+        let mut q = query("Conducted", "power_ports", "A");
+        q.metadata.power_range = Some("20kVA-75kVA".to_string());
+        let results = query_standards(&q).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].selector.as_ref().unwrap().interface.as_deref(), Some("dc_power"));
+
+        // ALSE radiated coverage ends at 2.345 GHz, FCC Part 15 at 40 GHz
+        let radiated = StandardQuery {
+            metadata: StandardMetadata { standard_type: Some("radiated".to_string()), ..Default::default() },
+            f_min: Some(50e9),
+            ..Default::default()
+        };
+        assert!(query_standards(&radiated).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_facets() {
+        let facets = facets(&catalog().unwrap());
+
+        assert_eq!(facets.standard_type, vec!["conducted", "radiated"]);
+        assert!(facets.class.contains(&"C3".to_string()));
+        assert!(facets.distance.contains(&"1m".to_string()));
+        assert!(!facets.class.contains(&"automotive".to_string()));
+    }
+
+    #[test]
+    fn test_automotive_class_numbers() {
+        // CISPR 25 / ECE R10 classes are selectable by number; "automotive" stays a power range
+        let mut q = query("conducted", "power_ports", "3");
+        q.metadata.power_range = Some("automotive".to_string());
+        let results = query_standards(&q).unwrap();
+        let keys: Vec<(&str, &str)> = results.iter().map(|r| (r.standard.as_str(), r.class.as_str())).collect();
+        assert_eq!(keys, vec![("CISPR25_Conducted_Current", "Class3"), ("CISPR25_Conducted_Voltage", "Class3")]);
+
+        assert_eq!(query_standards(&query("conducted", "dc_lines", "4")).unwrap()[0].standard, "ECE_R10_DC");
+    }
+}
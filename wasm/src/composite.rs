@@ -0,0 +1,311 @@
+// wasm/src/composite.rs
+// Composite limits: tightest-of envelope over several standards with per-segment attribution
+use serde::{Deserialize, Serialize};
+
+use crate::editions::{comparison_grid, limits_at};
+use crate::limit_ops::STEP;
+use crate::{Detector, DetectorRule, EMCStandard, EmcError, LimitUnit};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompositeMember {
+    pub standard: EMCStandard,
+    #[serde(default)]
+    pub distance: Option<f64>,  // m, measurement distance of radiated limits
+}
+
+// Frequency range over which one member sets a detector's composite limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoverningSegment {
+    pub detector: Detector,
+    pub f_start: f64,
+    pub f_stop: f64,
+    pub standard: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompositeLimit {
+    pub standard: EMCStandard,  // envelope as a regular mask standard
+    pub segments: Vec<GoverningSegment>,
+    pub distance: Option<f64>,  // m, distance the radiated limits were normalised to
+}
+
+// Inverse-distance field-strength scaling (dB) from one measurement distance to another
+pub fn distance_correction(from_m: f64, to_m: f64) -> f64 {
+    20.0 * (from_m / to_m).log10()
+}
+
+// Per-member offsets (dB) bringing every limit to the common measurement distance
+fn normalisation_offsets(members: &[CompositeMember], target_distance: Option<f64>) -> Result<(LimitUnit, Vec<f64>), EmcError> {
+    let unit = members.first().ok_or_else(|| EmcError::invalid("A composite limit needs at least one standard"))?.standard.unit;
+    if let Some(other) = members.iter().find(|m| m.standard.unit != unit) {
+        return Err(EmcError::UnitMismatch {
+            context: format!("Composite of '{}' and '{}'", members[0].standard.name, other.standard.name),
+            expected: unit,
+            found: other.standard.unit,
+        });
+    }
+
+    let target = match target_distance {
+        Some(d) if !d.is_finite() || d <= 0.0 => return Err(EmcError::invalid("Target distance must be positive")),
+        Some(d) => Some(d),
+        None => members.iter().find_map(|m| m.distance),
+    };
+
+    // Without a target distance the members must already agree
+    if target_distance.is_none() && members.iter().any(|m| m.distance.is_some_and(|d| Some(d) != target)) {
+        return Err(EmcError::invalid("Members have different distances; give a target distance"));
+    }
+
+    let offsets = members
+        .iter()
+        .map(|m| match (m.distance, target) {
+            (Some(d), _) if !d.is_finite() || d <= 0.0 => Err(EmcError::invalid(format!("'{}' has an invalid distance", m.standard.name))),
+            (Some(d), Some(t)) => Ok(distance_correction(d, t)),
+            (None, Some(_)) if unit == LimitUnit::DbMicroVoltPerMeter => {
+                Err(EmcError::invalid(format!("'{}' needs a measurement distance to be normalised", m.standard.name)))
+            }
+            _ => Ok(0.0),
+        })
+        .collect::<Result<Vec<f64>, EmcError>>()?;
+
+    Ok((unit, offsets))
+}
+
+// Tightest applicable member limit at a frequency: (member index, limit).
+// Ties keep the previously governing member, otherwise go to the earlier one.
+fn governing(members: &[CompositeMember], offsets: &[f64], frequency: f64, detector: usize, previous: Option<usize>) -> Option<(usize, f64)> {
+    members
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (i, limits_at(&m.standard, frequency)[detector] + offsets[i]))
+        .filter(|(_, limit)| !limit.is_nan())
+        .fold(None, |best, (i, limit)| match best {
+            Some((_, b)) if b < limit => best,
+            Some((_, b)) if b == limit && previous != Some(i) => best,
+            _ => Some((i, limit)),
+        })
+}
+
+// Envelope of minimum limits per detector; ties go to the earlier member
+pub fn composite_limits(
+    name: &str,
+    members: &[CompositeMember],
+    target_distance: Option<f64>,
+    points_per_decade: usize,
+) -> Result<CompositeLimit, EmcError> {
+    let (unit, offsets) = normalisation_offsets(members, target_distance)?;
+    let standards: Vec<&EMCStandard> = members.iter().map(|m| &m.standard).collect();
+    let mut grid = comparison_grid(&standards, points_per_decade);
+    // Band limits are flat with vertical edges: sample just either side of every edge
+    for band in standards.iter().flat_map(|s| s.bands.iter().flatten()) {
+        for edge in [band.f_start, band.f_stop] {
+            grid.extend([edge * (1.0 - STEP), edge * (1.0 + STEP)]);
+        }
+    }
+    grid.sort_by(|a, b| a.total_cmp(b));
+    grid.dedup();
+
+    let mut masks: Vec<Vec<(f64, f64)>> = Vec::new();
+    let mut segments = Vec::new();
+    let mut rules: Vec<DetectorRule> = Vec::new();
+
+    for detector in Detector::ALL {
+        let d = detector.index();
+        let mut points: Vec<(f64, Option<usize>, f64)> = Vec::new();
+
+        for &f in &grid {
+            let previous = points.last().and_then(|point| point.1);
+            let current = governing(members, &offsets, f, d, previous);
+
+            // Where the governing member changes between grid points, insert the exact crossing
+            if let (Some(&(f_prev, Some(a), _)), Some((b, _))) = (points.last(), current) {
+                if a != b {
+                    let limit = |i: usize, f: f64| limits_at(&members[i].standard, f)[d] + offsets[i];
+                    let gap_prev = limit(a, f_prev) - limit(b, f_prev);
+                    let gap_next = limit(a, f) - limit(b, f);
+                    if gap_prev.is_finite() && gap_next.is_finite() && gap_prev < 0.0 && gap_next > 0.0 {
+                        let ratio = -gap_prev / (gap_next - gap_prev);
+                        let crossing = (f_prev.ln() + ratio * (f.ln() - f_prev.ln())).exp();
+                        points.push((crossing, Some(a), limit(a, crossing)));
+                    }
+                }
+            }
+
+            match current {
+                Some((i, limit)) => points.push((f, Some(i), limit)),
+                None => points.push((f, None, f64::NAN)),
+            }
+        }
+
+        let mut current: Option<GoverningSegment> = None;
+        for &(f, member, _) in &points {
+            match (&mut current, member) {
+                (Some(segment), Some(i)) if segment.standard == members[i].standard.name => segment.f_stop = f,
+                (_, member) => {
+                    // Adjacent segments share their boundary frequency
+                    let f_start = current.as_ref().map_or(f, |segment| segment.f_stop);
+                    segments.extend(current.take());
+                    current = member.map(|i| GoverningSegment {
+                        detector,
+                        f_start,
+                        f_stop: f,
+                        standard: members[i].standard.name.clone(),
+                    });
+                }
+            }
+        }
+        segments.extend(current);
+
+        // Frequencies where no member has a limit are left out of the mask. The mask would bridge
+        // them, so each gap gets a no-limit rule; a detector without any limit gets an unbounded one.
+        let mask: Vec<(f64, f64)> = points.iter().filter(|point| point.1.is_some()).map(|&(f, _, limit)| (f, limit)).collect();
+        let no_limit = |f_start, f_stop| DetectorRule { detector, reference: None, offset: 0.0, f_start, f_stop };
+        if mask.is_empty() {
+            rules.push(no_limit(None, None));
+        } else {
+            let mut gap: Option<(f64, f64)> = None;
+            for &(f, member, _) in &points {
+                match member {
+                    None => gap = Some((gap.map_or(f, |(f_start, _)| f_start), f)),
+                    Some(_) => rules.extend(gap.take().map(|(f_start, f_stop)| no_limit(Some(f_start), Some(f_stop)))),
+                }
+            }
+            rules.extend(gap.map(|(f_start, f_stop)| no_limit(Some(f_start), Some(f_stop))));
+        }
+        masks.push(mask);
+    }
+
+    let split = |mask: &Vec<(f64, f64)>| -> Option<(Vec<f64>, Vec<f64>)> { (!mask.is_empty()).then(|| mask.iter().cloned().unzip()) };
+    let (f_avg, dbuv_avg) = split(&masks[0]).unwrap_or_default();
+    let (f_qp, dbuv_qp) = split(&masks[1]).unzip();
+    let (f_pk, dbuv_pk) = split(&masks[2]).unzip();
+    let names: Vec<&str> = members.iter().map(|m| m.standard.name.as_str()).collect();
+
+    let standard = EMCStandard {
+        name: name.to_string(),
+        description: format!("Tightest of {}", names.join(", ")),
+        unit,
+        f_avg_limit_mask: f_avg,
+        dbuv_avg_limit_mask: dbuv_avg,
+        f_qp_limit_mask: f_qp,
+        dbuv_qp_limit_mask: dbuv_qp,
+        f_pk_limit_mask: f_pk,
+        dbuv_pk_limit_mask: dbuv_pk,
+        bands: None,
+        detector_rules: (!rules.is_empty()).then_some(rules),
+        edition: None,
+    };
+
+    let distance = if unit == LimitUnit::DbMicroVoltPerMeter {
+        target_distance.or_else(|| members.iter().find_map(|m| m.distance))
+    } else {
+        None
+    };
+
+    Ok(CompositeLimit { standard, segments, distance })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(standard: &str, class: &str) -> CompositeMember {
+        CompositeMember { standard: EMCStandard::get_standard(standard, class).unwrap(), distance: None }
+    }
+
+    #[test]
+    fn test_envelope_and_attribution() {
+        let members = vec![member("CISPR22", "ClassB"), member("EN55032", "ClassB")];
+        let composite = composite_limits("Worldwide", &members, None, 20).unwrap();
+
+        // Identical AVG/QP go to the first member; the tighter EN 55032 peak governs below 30 MHz
+        let limits = composite.standard.interp_log(1e6);
+        assert_eq!(limits.dbuv_avg_limit, 56.0);
+        assert!((limits.dbuv_pk_limit - 72.0).abs() < 1e-9);
+        assert!(composite.segments.iter().filter(|s| s.detector == Detector::Avg).all(|s| s.standard == "CISPR 22 Class B"));
+        assert!(composite.segments.iter().any(|s| s.detector == Detector::Pk && s.standard == "EN 55032 Class B" && s.f_start == 150e3));
+    }
+
+    #[test]
+    fn test_crossing_inserted() {
+        // Between 5 and 30 MHz CISPR 22 Class B AVG falls 56 -> 30 dBµV while CISPR 11 Class B rises 46 -> 50
+        let parameters = crate::SelectionParameters { interface: Some("ac_mains".to_string()), ..Default::default() };
+        let cispr11 = EMCStandard::get_standard_with("CISPR11_Group1", "ClassB", &parameters).unwrap();
+        let members = vec![member("CISPR22", "ClassB"), CompositeMember { standard: cispr11, distance: None }];
+        let composite = composite_limits("Mains", &members, None, 5).unwrap();
+
+        let crossing = 5e6 * 6.0_f64.powf(1.0 / 3.0);
+        assert!(composite.standard.f_avg_limit_mask.iter().any(|&f| (f - crossing).abs() < 1.0));
+        let avg: Vec<&GoverningSegment> = composite.segments.iter().filter(|s| s.detector == Detector::Avg).collect();
+        assert_eq!(avg.len(), 2);
+        assert!((avg[0].f_stop - crossing).abs() < 1.0);
+        assert_eq!(avg[1].standard, "CISPR 22 Class B");
+
+        for &probe in &[200e3, 1e6, 10e6, 20e6, 300e6] {
+            let composite_avg = composite.standard.interp_log(probe).dbuv_avg_limit;
+            let tightest = members
+                .iter()
+                .map(|m| limits_at(&m.standard, probe)[0])
+                .filter(|l| !l.is_nan())
+                .fold(f64::INFINITY, f64::min);
+            assert!((composite_avg - tightest).abs() < 1e-6, "{} Hz: {} vs {}", probe, composite_avg, tightest);
+        }
+    }
+
+    #[test]
+    fn test_normalisation() {
+        assert!((distance_correction(1.0, 3.0) + 9.542).abs() < 1e-3);
+
+        let alse = member("CISPR25_Radiated_ALSE", "Class5");
+        let at_1m = CompositeMember { distance: Some(1.0), ..alse.clone() };
+        let composite = composite_limits("ALSE @ 3 m", &[at_1m], Some(3.0), 10).unwrap();
+        let original = alse.standard.interp_log(100e6).dbuv_avg_limit;
+        assert!((composite.standard.interp_log(100e6).dbuv_avg_limit - (original - 9.542)).abs() < 1e-2);
+
+        // Radiated member without a distance, and mixed units, are rejected
+        assert!(composite_limits("x", &[alse], Some(3.0), 10).is_err());
+        let mixed = [member("CISPR22", "ClassB"), member("CISPR32_Telecom_Current", "ClassB")];
+        assert_eq!(composite_limits("x", &mixed, None, 10).unwrap_err().code(), "UNIT_MISMATCH");
+    }
+
+    #[test]
+    fn test_band_table_round_trip() {
+        let members = vec![member("CISPR25_Conducted_Voltage", "Class3"), member("CISPR25_Conducted_Voltage", "Class5")];
+        let composite = composite_limits("CISPR 25 Class 3/5", &members, None, 10).unwrap();
+        let json = serde_json::to_string(&composite.standard).unwrap();
+        let standard = EMCStandard::from_json(&json).unwrap();
+
+        // Flat within every band right up to its edges, never a ramp across them
+        let bands = members[0].standard.bands.clone().unwrap();
+        for band in &bands {
+            let inside = [band.f_start * (1.0 + 10.0 * STEP), (band.f_start * band.f_stop).sqrt(), band.f_stop * (1.0 - 10.0 * STEP)];
+            for &f in &inside {
+                let limits = standard.interp_log(f);
+                let composite_limits = [limits.dbuv_avg_limit, limits.dbuv_qp_limit, limits.dbuv_pk_limit];
+                for (d, composite_limit) in composite_limits.iter().enumerate() {
+                    let tightest = members.iter().map(|m| limits_at(&m.standard, f)[d]).filter(|l| !l.is_nan()).fold(f64::NAN, f64::min);
+                    if !tightest.is_nan() {
+                        assert!((composite_limit - tightest).abs() < 1e-6, "{} Hz detector {}: {} vs {}", f, d, composite_limit, tightest);
+                    }
+                }
+            }
+        }
+
+        // No limit between the bands, where a single mask would otherwise interpolate
+        let single = composite_limits("CISPR 25 Class 3", &members[..1], None, 10).unwrap().standard;
+        for &f in &[400e3, 20e6] {
+            assert!(members[0].standard.interp_log(f).dbuv_avg_limit.is_nan());
+            let limits = single.interp_log(f);
+            assert!(limits.dbuv_avg_limit.is_nan() && limits.dbuv_qp_limit.is_nan() && limits.dbuv_pk_limit.is_nan(), "{} Hz", f);
+            assert!(single.check_compliance(&[f], &[90.0], Detector::Avg).unwrap()[0].passes);
+        }
+        let gap = (bands[0].f_stop * bands[1].f_start).sqrt();
+        assert!(standard.interp_log(gap).dbuv_qp_limit.is_nan());
+
+        // A member without any peak limit still yields a loadable standard
+        let fcc = composite_limits("FCC", &[member("FCC_Part15_Conducted", "ClassB")], None, 10).unwrap();
+        let fcc = EMCStandard::from_json(&serde_json::to_string(&fcc.standard).unwrap()).unwrap();
+        assert_eq!(fcc.interp_log(1e6).dbuv_qp_limit, 56.0);
+        assert!(fcc.f_pk_limit_mask.is_none() && fcc.interp_log(1e6).dbuv_pk_limit.is_nan());
+    }
+}
@@ -0,0 +1,92 @@
+// wasm/src/current_probe.rs
+// Current-probe transfer impedance: probe voltage (dBµV) -> conducted current (dBµA)
+use serde::{Deserialize, Serialize};
+
+use crate::transducers::TransducerTable;
+use crate::{ComplianceResult, Detector, EMCStandard, EmcError, LimitUnit};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentProbe {
+    pub name: String,
+    pub transfer_impedance: Vec<(f64, f64)>,  // (frequency_hz, z_t_dbohm)
+}
+
+impl CurrentProbe {
+    pub fn new(name: &str, transfer_impedance: Vec<(f64, f64)>) -> Self {
+        Self { name: name.to_string(), transfer_impedance }
+    }
+
+    // Probe with a frequency-independent transfer impedance (Ω)
+    pub fn flat(name: &str, ohms: f64) -> Self {
+        let z_t = 20.0 * ohms.log10();
+        Self::new(name, vec![(9e3, z_t), (1e9, z_t)])
+    }
+
+    // Correction table: I [dBµA] = V [dBµV] - Z_t [dBΩ]
+    pub fn to_transducer(&self) -> TransducerTable {
+        let points = self.transfer_impedance.iter().map(|&(f, z_t)| (f, -z_t)).collect();
+        TransducerTable::new(&self.name, points)
+    }
+
+    pub fn voltage_to_current(&self, frequencies: &[f64], probe_dbuv: &[f64]) -> Result<Vec<f64>, EmcError> {
+        self.to_transducer().apply(frequencies, probe_dbuv)
+    }
+}
+
+// Convert probe readings to current and check them against a dBµA standard
+pub fn check_current_compliance(
+    standard: &EMCStandard,
+    probe: &CurrentProbe,
+    frequencies: &[f64],
+    probe_dbuv: &[f64],
+    detector: Detector,
+) -> Result<Vec<ComplianceResult>, EmcError> {
+    if standard.unit != LimitUnit::DbMicroAmp {
+        return Err(EmcError::invalid(format!(
+            "'{}' has {:?} limits; current-probe readings need a dBµA standard",
+            standard.name, standard.unit
+        )));
+    }
+
+    let current = probe.voltage_to_current(frequencies, probe_dbuv)?;
+    standard.check_compliance(frequencies, &current, detector)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flat_probe_conversion() {
+        // 5 Ω probe: 14 dBΩ
+        let probe = CurrentProbe::flat("5 ohm", 5.0);
+        let current = probe.voltage_to_current(&[1e6, 10e6], &[50.0, 40.0]).unwrap();
+
+        assert!((current[0] - (50.0 - 13.979)).abs() < 1e-3);
+        assert!((current[1] - (40.0 - 13.979)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_current_standard_compliance() {
+        let standard = EMCStandard::get_standard("CISPR32_Telecom_Current", "ClassB").unwrap();
+        assert_eq!(standard.unit, LimitUnit::DbMicroAmp);
+        // AVG 30 -> 20 dBµA over 0.15 - 0.5 MHz, 10 dB below QP
+        assert_eq!(standard.interp_log(150e3).dbuv_avg_limit, 30.0);
+
+        // 1 Ω probe, Class B QP current limit is 30 dBµA above 500 kHz
+        let probe = CurrentProbe::new("1 ohm", vec![(1e5, 0.0), (1e8, 0.0)]);
+        let results = check_current_compliance(&standard, &probe, &[1e6, 10e6], &[25.0, 35.0], Detector::Qp).unwrap();
+
+        assert!(results[0].passes);
+        assert!(!results[1].passes);
+        assert!((results[1].margin + 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_voltage_standard_rejected() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let probe = CurrentProbe::flat("1 ohm", 1.0);
+
+        assert!(check_current_compliance(&standard, &probe, &[1e6], &[25.0], Detector::Qp).is_err());
+    }
+}
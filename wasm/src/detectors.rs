@@ -0,0 +1,322 @@
+// wasm/src/detectors.rs
+// Software emulation of the CISPR 16-1-1 peak, quasi-peak and CISPR-average detectors
+use serde::{Deserialize, Serialize};
+use std::f64::consts::SQRT_2;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::frequency_helpers::frequency_bands::CisprBand;
+use crate::time_domain::{fft, gaussian_filter_gain, ifft, volts_to_dbuv};
+use crate::EmcError;
+
+// The envelope is decimated to this many samples per RBW before detection
+const ENVELOPE_OVERSAMPLING: f64 = 8.0;
+
+// Detectors run until this many (discharge + meter) time constants have elapsed
+const SETTLING_TIME_CONSTANTS: f64 = 10.0;
+
+// Detector a limit or a trace refers to; names are parsed case-insensitively
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", try_from = "String")]
+pub enum Detector {
+    #[default]
+    Avg,
+    Qp,
+    Pk,
+}
+
+impl Detector {
+    // In the order of the avg / qp / pk limit fields
+    pub const ALL: [Detector; 3] = [Detector::Avg, Detector::Qp, Detector::Pk];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Detector::Avg => "avg",
+            Detector::Qp => "qp",
+            Detector::Pk => "pk",
+        }
+    }
+
+    // Position in `ALL`, for per-detector arrays
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl FromStr for Detector {
+    type Err = EmcError;
+
+    fn from_str(name: &str) -> Result<Detector, EmcError> {
+        Detector::ALL
+            .into_iter()
+            .find(|d| d.as_str().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| EmcError::InvalidDetector { detector: name.to_string() })
+    }
+}
+
+impl TryFrom<String> for Detector {
+    type Error = EmcError;
+
+    fn try_from(name: String) -> Result<Detector, EmcError> {
+        name.parse()
+    }
+}
+
+impl fmt::Display for Detector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// Readings in dBµV, calibrated to the RMS value of a sine wave like a real receiver
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectorReadings {
+    pub frequency: f64,
+    pub band: CisprBand,
+    pub pk: f64,
+    pub qp: Option<f64>,  // None above 1 GHz
+    pub avg: f64,
+}
+
+// First-order smoothing coefficient for a time step `dt` and time constant `tau`
+fn smoothing(dt: f64, tau: f64) -> f64 {
+    1.0 - (-dt / tau).exp()
+}
+
+// Critically damped meter, modelled as two cascaded first-order sections
+struct Meter {
+    alpha: f64,
+    stage1: f64,
+    stage2: f64,
+}
+
+impl Meter {
+    fn new(dt: f64, time_constant: f64) -> Self {
+        Self { alpha: smoothing(dt, time_constant), stage1: 0.0, stage2: 0.0 }
+    }
+
+    fn update(&mut self, input: f64) -> f64 {
+        self.stage1 += (input - self.stage1) * self.alpha;
+        self.stage2 += (self.stage1 - self.stage2) * self.alpha;
+        self.stage2
+    }
+}
+
+// Keep every n-th sample so the envelope rate stays a few times above the RBW
+fn decimate(envelope: &[f64], sample_rate: f64, rbw: f64) -> (Vec<f64>, f64) {
+    let factor = ((sample_rate / (ENVELOPE_OVERSAMPLING * rbw)).floor() as usize).max(1);
+    let decimated = envelope.iter().step_by(factor).copied().collect();
+    (decimated, sample_rate / factor as f64)
+}
+
+// Number of samples to simulate: the envelope is treated as one period of a repetitive
+// signal and replayed until the detector and meter have settled
+fn settled_length(len: usize, sample_rate: f64, settling_time: f64) -> usize {
+    let needed = (settling_time * sample_rate).ceil() as usize;
+    let periods = needed.div_ceil(len).max(1) + 1;
+    periods * len
+}
+
+// Quasi-peak reading (V, envelope units) of an IF envelope
+pub fn quasi_peak(envelope: &[f64], sample_rate: f64, band: CisprBand) -> Option<f64> {
+    let constants = band.qp_time_constants()?;
+    if envelope.is_empty() {
+        return Some(0.0);
+    }
+
+    let (envelope, sample_rate) = decimate(envelope, sample_rate, band.rbw());
+    let dt = 1.0 / sample_rate;
+    let charge = smoothing(dt, constants.charge);
+    let discharge = (-dt / constants.discharge).exp();
+    let mut meter = Meter::new(dt, constants.meter);
+
+    let total = settled_length(
+        envelope.len(),
+        sample_rate,
+        SETTLING_TIME_CONSTANTS * (constants.discharge + constants.meter),
+    );
+    let last_period = total - envelope.len();
+
+    let mut capacitor = 0.0;
+    let mut reading = 0.0_f64;
+    for i in 0..total {
+        let input = envelope[i % envelope.len()];
+        if input > capacitor {
+            capacitor += (input - capacitor) * charge;
+        } else {
+            capacitor *= discharge;
+        }
+
+        let indicated = meter.update(capacitor);
+        if i >= last_period {
+            reading = reading.max(indicated);
+        }
+    }
+
+    Some(reading)
+}
+
+// CISPR-average reading: linear average weighted by the meter time constant, maximum indication
+pub fn cispr_average(envelope: &[f64], sample_rate: f64, band: CisprBand) -> f64 {
+    if envelope.is_empty() {
+        return 0.0;
+    }
+
+    let (envelope, sample_rate) = decimate(envelope, sample_rate, band.rbw());
+    let dt = 1.0 / sample_rate;
+    let time_constant = band.meter_time_constant();
+    let mut meter = Meter::new(dt, time_constant);
+
+    let total = settled_length(envelope.len(), sample_rate, SETTLING_TIME_CONSTANTS * time_constant);
+    let last_period = total - envelope.len();
+
+    let mut reading = 0.0_f64;
+    for i in 0..total {
+        let indicated = meter.update(envelope[i % envelope.len()]);
+        if i >= last_period {
+            reading = reading.max(indicated);
+        }
+    }
+
+    reading
+}
+
+// Apply all three detectors to an IF envelope (linear volts, peak of the IF signal)
+pub fn detect_envelope(envelope: &[f64], sample_rate: f64, frequency: f64) -> Result<DetectorReadings, EmcError> {
+    let band = CisprBand::from_frequency(frequency)
+        .ok_or_else(|| EmcError::invalid(format!("{} Hz is outside the CISPR 16-1-1 bands", frequency)))?;
+    if !sample_rate.is_finite() || sample_rate <= 0.0 {
+        return Err(EmcError::invalid("sample_rate must be positive"));
+    }
+
+    let peak = envelope.iter().fold(0.0_f64, |a, &b| a.max(b));
+    let qp = quasi_peak(envelope, sample_rate, band);
+    let avg = cispr_average(envelope, sample_rate, band);
+
+    Ok(DetectorReadings {
+        frequency,
+        band,
+        pk: volts_to_dbuv(peak / SQRT_2),
+        qp: qp.map(|v| volts_to_dbuv(v / SQRT_2)),
+        avg: volts_to_dbuv(avg / SQRT_2),
+    })
+}
+
+// IF envelope of a time-domain capture for a receiver tuned to `frequency`
+pub fn if_envelope(samples: &[f64], sample_rate: f64, frequency: f64, rbw: f64) -> Vec<f64> {
+    let n_fft = samples.len().next_power_of_two();
+    let mut re = vec![0.0; n_fft];
+    let mut im = vec![0.0; n_fft];
+    re[..samples.len()].copy_from_slice(samples);
+    fft(&mut re, &mut im);
+
+    // Analytic signal of the filtered capture: positive frequencies only, doubled
+    let bin_spacing = sample_rate / n_fft as f64;
+    for k in 0..n_fft {
+        let gain = if k > 0 && k < n_fft / 2 {
+            2.0 * gaussian_filter_gain(k as f64 * bin_spacing - frequency, rbw)
+        } else {
+            0.0
+        };
+        re[k] *= gain;
+        im[k] *= gain;
+    }
+    ifft(&mut re, &mut im);
+
+    re.iter()
+        .zip(im.iter())
+        .take(samples.len())
+        .map(|(r, i)| (r * r + i * i).sqrt())
+        .collect()
+}
+
+// Peak, quasi-peak and CISPR-average readings of a capture at each requested frequency
+pub fn detect_capture(samples: &[f64], sample_rate: f64, frequencies: &[f64]) -> Result<Vec<DetectorReadings>, EmcError> {
+    if samples.len() < 2 {
+        return Err(EmcError::invalid("At least two samples are required"));
+    }
+
+    frequencies
+        .iter()
+        .map(|&frequency| {
+            let band = CisprBand::from_frequency(frequency)
+                .ok_or_else(|| EmcError::invalid(format!("{} Hz is outside the CISPR 16-1-1 bands", frequency)))?;
+            if frequency >= sample_rate / 2.0 {
+                return Err(EmcError::invalid(format!("{} Hz is above the Nyquist frequency of the capture", frequency)));
+            }
+            let envelope = if_envelope(samples, sample_rate, frequency, band.rbw());
+            detect_envelope(&envelope, sample_rate, frequency)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    // Rectangular envelope pulses of `width` seconds repeating at `prf`
+    fn pulse_envelope(sample_rate: f64, prf: f64, width: f64) -> Vec<f64> {
+        let period = (sample_rate / prf).round() as usize;
+        let on = (sample_rate * width).round() as usize;
+        (0..period).map(|i| if i < on { 1.0 } else { 0.0 }).collect()
+    }
+
+    #[test]
+    fn test_constant_envelope_reads_equal() {
+        let envelope = vec![1e-3 * SQRT_2; 1000];
+        let readings = detect_envelope(&envelope, 1e6, 1e6).unwrap();
+
+        assert_eq!(readings.band, CisprBand::B);
+        assert!((readings.pk - 60.0).abs() < 1e-9);
+        assert!((readings.qp.unwrap() - 60.0).abs() < 0.1);
+        assert!((readings.avg - 60.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_pulse_train_detector_ordering() {
+        let envelope = pulse_envelope(1e6, 100.0, 100e-6);
+        let readings = detect_envelope(&envelope, 1e6, 1e6).unwrap();
+        let qp = readings.qp.unwrap();
+
+        assert!(readings.pk > qp);
+        assert!(qp > readings.avg);
+        // 1% duty cycle -> average is ~40 dB below peak
+        assert!((readings.pk - readings.avg - 40.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_qp_rises_with_repetition_rate() {
+        let slow = quasi_peak(&pulse_envelope(1e6, 10.0, 100e-6), 1e6, CisprBand::B).unwrap();
+        let fast = quasi_peak(&pulse_envelope(1e6, 1000.0, 100e-6), 1e6, CisprBand::B).unwrap();
+
+        assert!(fast > slow);
+        assert!(quasi_peak(&[1.0], 1e6, CisprBand::E).is_none());
+    }
+
+    #[test]
+    fn test_capture_sine_detection() {
+        let sample_rate = 10e6;
+        // 1 mV peak at 2 MHz -> 57 dBµV on all detectors
+        let samples: Vec<f64> = (0..1 << 14)
+            .map(|i| 1e-3 * (2.0 * PI * 2e6 * i as f64 / sample_rate).sin())
+            .collect();
+
+        let readings = detect_capture(&samples, sample_rate, &[2e6]).unwrap();
+        assert_eq!(readings.len(), 1);
+        assert!((readings[0].qp.unwrap() - 57.0).abs() < 1.0);
+        assert!((readings[0].avg - 57.0).abs() < 1.0);
+        assert!(detect_capture(&samples, sample_rate, &[6e6]).is_err());
+    }
+
+    #[test]
+    fn test_detector_names() {
+        assert_eq!("QP".parse::<Detector>().unwrap(), Detector::Qp);
+        assert_eq!(" avg ".parse::<Detector>().unwrap(), Detector::Avg);
+        assert_eq!("rms".parse::<Detector>().unwrap_err(), EmcError::InvalidDetector { detector: "rms".to_string() });
+
+        assert_eq!(serde_json::to_string(&Detector::ALL).unwrap(), r#"["avg","qp","pk"]"#);
+        assert_eq!(serde_json::from_str::<Detector>(r#""Pk""#).unwrap(), Detector::Pk);
+        assert!(serde_json::from_str::<Detector>(r#""peak""#).is_err());
+    }
+}
@@ -0,0 +1,279 @@
+// wasm/src/editions.rs
+// Standard editions (edition, date, supersedes), lookup by edition and limit diffs between editions
+use serde::{Deserialize, Serialize};
+
+use crate::frequency_helpers::standard_definitions::create_log_range;
+use crate::{Detector, EMCStandard, EmcError, StandardClass};
+
+// Differences below this are treated as identical limits (dB)
+const DIFF_TOLERANCE: f64 = 0.01;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StandardEdition {
+    pub family: String,    // e.g. "CISPR 25"
+    pub edition: String,   // e.g. "4.0"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,        // publication date, YYYY-MM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<String>,  // key of the standard this one replaces
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditionInfo {
+    pub standard: String,  // key for get_standard
+    pub edition: StandardEdition,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitChange {
+    Tightened,
+    Relaxed,
+    Added,    // limit applies only in the newer edition
+    Removed,  // limit applies only in the older edition
+}
+
+// Contiguous frequency range over which one detector's limit changed the same way
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitDifference {
+    pub detector: Detector,
+    pub change: LimitChange,
+    pub f_start: f64,
+    pub f_stop: f64,
+    pub max_delta: f64,  // dB, new - old with the largest magnitude; NaN for added/removed
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitsDiff {
+    pub from: String,
+    pub to: String,
+    pub from_edition: Option<StandardEdition>,
+    pub to_edition: Option<StandardEdition>,
+    pub differences: Vec<LimitDifference>,
+}
+
+impl LimitsDiff {
+    pub fn identical(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+// All editions of a family, oldest first
+pub fn list_editions(family: &str) -> Result<Vec<EditionInfo>, EmcError> {
+    let data = EMCStandard::load_standards_data()?;
+
+    let mut editions: Vec<EditionInfo> = data
+        .standards
+        .iter()
+        .filter_map(|(standard, classes)| {
+            let edition = classes.values().find_map(|class| class.edition.clone())?;
+            edition.family.eq_ignore_ascii_case(family).then(|| EditionInfo { standard: standard.clone(), edition })
+        })
+        .collect();
+
+    editions.sort_by(|a, b| (&a.edition.date, &a.standard).cmp(&(&b.edition.date, &b.standard)));
+    Ok(editions)
+}
+
+// Whether a class matches an edition-lookup filter: its standard key, measurement type or port subtype
+fn matches_filter(key: &str, class: &StandardClass, filter: &str) -> bool {
+    let metadata = class.metadata.clone().unwrap_or_default();
+    key.eq_ignore_ascii_case(filter)
+        || [metadata.measurement_type, metadata.subtype].iter().flatten().any(|value| value.eq_ignore_ascii_case(filter))
+}
+
+// Class of a given family edition. A family may span several keys (e.g. one per measurement
+// method or port); `filter` (standard key, measurement type or subtype) then picks one of them.
+pub fn get_standard_edition(family: &str, edition: &str, class_name: &str, filter: Option<&str>) -> Result<EMCStandard, EmcError> {
+    let label = format!("{} edition {}", family, edition);
+    let candidates: Vec<EditionInfo> = list_editions(family)?
+        .into_iter()
+        .filter(|info| info.edition.edition.eq_ignore_ascii_case(edition))
+        .collect();
+
+    if candidates.is_empty() {
+        return Err(EmcError::StandardNotFound { standard: label });
+    }
+
+    let data = EMCStandard::load_standards_data()?;
+    let classes = |info: &EditionInfo| data.standards.get(&info.standard).into_iter().flatten();
+    let matches: Vec<&str> = candidates
+        .iter()
+        .filter(|info| {
+            classes(info).any(|(key, class)| key == class_name && filter.is_none_or(|filter| matches_filter(&info.standard, class, filter)))
+        })
+        .map(|info| info.standard.as_str())
+        .collect();
+
+    match matches.as_slice() {
+        [] => {
+            let mut available: Vec<String> = candidates.iter().flat_map(|info| classes(info).map(|(key, _)| key.clone())).collect();
+            available.sort();
+            available.dedup();
+            Err(EmcError::ClassNotFound { standard: label, class: class_name.to_string(), available })
+        }
+        [key] => EMCStandard::get_standard(key, class_name),
+        _ => Err(EmcError::invalid(format!(
+            "Class '{}' of '{}' is ambiguous between {}; give a standard key, measurement type or port",
+            class_name,
+            label,
+            matches.join(", ")
+        ))),
+    }
+}
+
+// Limits inside the standard's coverage; NaN (not applicable) outside it
+pub(crate) fn limits_at(standard: &EMCStandard, frequency: f64) -> [f64; 3] {
+    let (lo, hi) = standard.frequency_span();
+    if frequency < lo || frequency > hi {
+        return [f64::NAN; 3];
+    }
+    let limits = standard.interp_log(frequency);
+    [limits.dbuv_avg_limit, limits.dbuv_qp_limit, limits.dbuv_pk_limit]
+}
+
+// Log grid over the combined coverage plus every breakpoint, so steps are never skipped
+pub(crate) fn comparison_grid(standards: &[&EMCStandard], points_per_decade: usize) -> Vec<f64> {
+    let (lo, hi) = standards.iter().map(|s| s.frequency_span()).fold(
+        (f64::INFINITY, f64::NEG_INFINITY),
+        |(lo, hi), (s_lo, s_hi)| (lo.min(s_lo), hi.max(s_hi)),
+    );
+
+    let mut grid = if lo.is_finite() && hi.is_finite() { create_log_range(lo, hi, points_per_decade) } else { Vec::new() };
+    for standard in standards {
+        grid.extend(standard.breakpoints());
+    }
+    grid.sort_by(|a, b| a.total_cmp(b));
+    grid.dedup();
+    grid
+}
+
+fn classify(old: f64, new: f64) -> Option<LimitChange> {
+    match (old.is_nan(), new.is_nan()) {
+        (true, true) => None,
+        (true, false) => Some(LimitChange::Added),
+        (false, true) => Some(LimitChange::Removed),
+        _ if (new - old).abs() < DIFF_TOLERANCE => None,
+        _ if new < old => Some(LimitChange::Tightened),
+        _ => Some(LimitChange::Relaxed),
+    }
+}
+
+// Where and by how much two standards' limits differ, per detector
+pub fn diff_limits(from: &EMCStandard, to: &EMCStandard, points_per_decade: usize) -> Result<LimitsDiff, EmcError> {
+    if points_per_decade == 0 {
+        return Err(EmcError::invalid("points_per_decade must be at least 1"));
+    }
+    let grid = comparison_grid(&[from, to], points_per_decade);
+
+    let mut differences = Vec::new();
+    for detector in Detector::ALL {
+        let d = detector.index();
+        let mut current: Option<LimitDifference> = None;
+
+        for &f in &grid {
+            let (old, new) = (limits_at(from, f)[d], limits_at(to, f)[d]);
+            let change = classify(old, new);
+            let delta = new - old;
+
+            match (&mut current, change) {
+                (Some(segment), Some(change)) if segment.change == change => {
+                    segment.f_stop = f;
+                    if delta.abs() > segment.max_delta.abs() {
+                        segment.max_delta = delta;
+                    }
+                }
+                (_, change) => {
+                    differences.extend(current.take());
+                    current = change.map(|change| LimitDifference {
+                        detector,
+                        change,
+                        f_start: f,
+                        f_stop: f,
+                        max_delta: delta,
+                    });
+                }
+            }
+        }
+        differences.extend(current);
+    }
+
+    Ok(LimitsDiff {
+        from: from.name.clone(),
+        to: to.name.clone(),
+        from_edition: from.edition.clone(),
+        to_edition: to.edition.clone(),
+        differences,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edition_lookup() {
+        let standard = get_standard_edition("CISPR 32", "2.0", "ClassB", None).unwrap();
+        assert_eq!(standard.edition.as_ref().unwrap().date.as_deref(), Some("2015-03"));
+
+        // CISPR 25 Ed. 4 spans three keys, all with Class3: a measurement type or key picks one
+        assert_eq!(list_editions("cispr 25").unwrap().len(), 3);
+        assert!(get_standard_edition("CISPR 25", "4.0", "Class3", None).unwrap_err().to_string().contains("ambiguous"));
+        let current = get_standard_edition("CISPR 25", "4.0", "Class3", Some("current")).unwrap();
+        assert_eq!(current.name, "CISPR 25 Conducted Current Class 3");
+        let alse = get_standard_edition("CISPR 25", "4.0", "Class3", Some("CISPR25_Radiated_ALSE")).unwrap();
+        assert_eq!(alse.name, "CISPR 25 Radiated ALSE Class 3");
+        assert_eq!(get_standard_edition("CISPR 25", "3.0", "Class3", None).unwrap_err().code(), "STANDARD_NOT_FOUND");
+        match get_standard_edition("CISPR 32", "2.0", "ClassC", None).unwrap_err() {
+            EmcError::ClassNotFound { available, .. } => assert_eq!(available, vec!["ClassA", "ClassB"]),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Variants inherit the edition of their class
+        let parameters = crate::SelectionParameters { interface: Some("ac_mains".to_string()), ..Default::default() };
+        let cispr11 = EMCStandard::get_standard_with("CISPR11_Group1", "ClassB", &parameters).unwrap();
+        assert_eq!(cispr11.edition.unwrap().family, "CISPR 11");
+    }
+
+    #[test]
+    fn test_cispr22_to_en55032_diff() {
+        let old = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let new = EMCStandard::get_standard("EN55032", "ClassB").unwrap();
+        assert_eq!(new.edition.as_ref().unwrap().supersedes.as_deref(), Some("CISPR22"));
+
+        // Same AVG/QP masks; EN 55032 has no peak mask, and the QP + 10 dB fallback is up to
+        // 2 dB tighter than the CISPR 22 peak mask below 30 MHz
+        let diff = diff_limits(&old, &new, 10).unwrap();
+        assert_eq!(diff.differences.len(), 1);
+        let pk = &diff.differences[0];
+        assert_eq!(pk.detector, Detector::Pk);
+        assert_eq!(pk.change, LimitChange::Tightened);
+        assert!((pk.max_delta + 2.0).abs() < 1e-9);
+        assert_eq!(pk.f_start, 150e3);
+        assert!(pk.f_stop > 5e6 && pk.f_stop < 30e6);
+
+        assert!(diff_limits(&old, &old, 10).unwrap().identical());
+        assert!(diff_limits(&old, &new, 0).is_err());
+    }
+
+    #[test]
+    fn test_band_coverage_changes() {
+        let voltage = EMCStandard::get_standard("CISPR25_Conducted_Voltage", "Class3").unwrap();
+        let alse = EMCStandard::get_standard("CISPR25_Radiated_ALSE", "Class3").unwrap();
+
+        let diff = diff_limits(&voltage, &alse, 10).unwrap();
+        assert!(diff.differences.iter().any(|d| d.change == LimitChange::Added && d.f_start > 108e6));
+        assert!(diff.differences.iter().filter(|d| d.change == LimitChange::Added).all(|d| d.max_delta.is_nan()));
+    }
+
+    #[test]
+    fn test_ece_r10_ports() {
+        // Rev. 6 has one key per port; the port subtype picks one
+        let keys: Vec<String> = list_editions("ECE R10").unwrap().into_iter().map(|info| info.standard).collect();
+        assert_eq!(keys, vec!["ECE_R10_AC", "ECE_R10_DC"]);
+        assert!(get_standard_edition("ECE R10", "6", "Class3", None).unwrap_err().to_string().contains("ECE_R10_DC"));
+
+        let dc = get_standard_edition("ECE R10", "6", "Class3", Some("dc_lines")).unwrap();
+        assert_eq!(dc.name, "ECE R10 Class 3 DC Lines");
+        assert!(dc.edition.as_ref().unwrap().supersedes.is_none());
+    }
+}
@@ -0,0 +1,115 @@
+// wasm/src/error.rs
+// Typed errors of the native API; the JavaScript bindings pass them on as { code, message, details }
+use serde::Serialize;
+use std::fmt;
+
+use crate::validation::PointIssue;
+use crate::LimitUnit;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "code", content = "details", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EmcError {
+    StandardNotFound { standard: String },
+    ClassNotFound { standard: String, class: String, available: Vec<String> },
+    VariantNotFound { class: String, selectors: Vec<String> },  // no variant matches the selection parameters
+    InvalidDetector { detector: String },
+    InvalidMask { mask: String, reason: String },
+    LengthMismatch { field: String, expected: usize, found: usize },
+    InvalidPoint { index: usize, issue: PointIssue },  // 0-based index into the measured arrays
+    UnitMismatch { context: String, expected: LimitUnit, found: LimitUnit },
+    ParseError { line: usize, column: Option<usize>, message: String },  // 1-based positions
+    InvalidInput { message: String },
+}
+
+impl EmcError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            EmcError::StandardNotFound { .. } => "STANDARD_NOT_FOUND",
+            EmcError::ClassNotFound { .. } => "CLASS_NOT_FOUND",
+            EmcError::VariantNotFound { .. } => "VARIANT_NOT_FOUND",
+            EmcError::InvalidDetector { .. } => "INVALID_DETECTOR",
+            EmcError::InvalidMask { .. } => "INVALID_MASK",
+            EmcError::LengthMismatch { .. } => "LENGTH_MISMATCH",
+            EmcError::InvalidPoint { .. } => "INVALID_POINT",
+            EmcError::UnitMismatch { .. } => "UNIT_MISMATCH",
+            EmcError::ParseError { .. } => "PARSE_ERROR",
+            EmcError::InvalidInput { .. } => "INVALID_INPUT",
+        }
+    }
+
+    pub fn parse(line: usize, column: Option<usize>, message: impl Into<String>) -> EmcError {
+        EmcError::ParseError { line, column, message: message.into() }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> EmcError {
+        EmcError::InvalidInput { message: message.into() }
+    }
+}
+
+impl fmt::Display for EmcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmcError::StandardNotFound { standard } => write!(f, "Standard '{}' not found", standard),
+            EmcError::ClassNotFound { standard, class, available } => {
+                write!(f, "Class '{}' not found for standard '{}' (available: {})", class, standard, available.join(", "))
+            }
+            EmcError::VariantNotFound { class, selectors } => {
+                write!(f, "No variant of '{}' matches the selection; available selectors: {}", class, selectors.join(", "))
+            }
+            EmcError::InvalidDetector { detector } => {
+                write!(f, "Unknown detector '{}': measurement_type must be 'avg', 'qp', or 'pk'", detector)
+            }
+            EmcError::InvalidMask { mask, reason } => write!(f, "Invalid {} mask: {}", mask, reason),
+            EmcError::LengthMismatch { field, expected, found } => {
+                write!(f, "{} has {} values, expected {}", field, found, expected)
+            }
+            EmcError::InvalidPoint { index, issue } => write!(f, "Point {}: {}", index, issue),
+            EmcError::UnitMismatch { context, expected, found } => {
+                write!(f, "{}: {:?} limits cannot be combined with {:?} limits", context, expected, found)
+            }
+            EmcError::ParseError { line, column: Some(column), message } => write!(f, "line {}, column {}: {}", line, column, message),
+            EmcError::ParseError { line, column: None, message } => write!(f, "line {}: {}", line, message),
+            EmcError::InvalidInput { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for EmcError {}
+
+impl From<serde_json::Error> for EmcError {
+    fn from(error: serde_json::Error) -> EmcError {
+        // serde_json appends " at line L column C" to its message
+        let message = error.to_string();
+        let message = message.split(" at line ").next().unwrap_or(&message).to_string();
+        EmcError::ParseError { line: error.line(), column: Some(error.column()), message }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_and_details_serialisation() {
+        let error = EmcError::ClassNotFound { standard: "CISPR22".into(), class: "ClassC".into(), available: vec!["ClassA".into(), "ClassB".into()] };
+        let value = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(value["code"], error.code());
+        assert_eq!(value["details"]["class"], "ClassC");
+        assert_eq!(value["details"]["available"][1], "ClassB");
+        assert!(error.to_string().contains("ClassA, ClassB"));
+    }
+
+    #[test]
+    fn test_json_parse_position() {
+        let error: EmcError = serde_json::from_str::<Vec<f64>>("[1.0,\n 2.0,\n x]").unwrap_err().into();
+        match &error {
+            EmcError::ParseError { line, column, message } => {
+                assert_eq!((*line, *column), (3, Some(2)));
+                assert!(!message.contains("at line"));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(error.to_string().starts_with("line 3, column 2: "));
+    }
+}
@@ -0,0 +1,153 @@
+// wasm/src/filter_design.rs
+// EMI filter requirement calculator: required insertion loss and candidate corner frequencies
+use serde::{Deserialize, Serialize};
+
+use crate::{Detector, EMCStandard, EmcError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FilterTopology {
+    SingleStageLC,
+    TwoStageLC,
+}
+
+impl FilterTopology {
+    pub const ALL: [FilterTopology; 2] = [FilterTopology::SingleStageLC, FilterTopology::TwoStageLC];
+
+    pub fn order(&self) -> u32 {
+        match self {
+            FilterTopology::SingleStageLC => 2,
+            FilterTopology::TwoStageLC => 4,
+        }
+    }
+
+    // Asymptotic attenuation slope above the corner frequency (dB/decade)
+    pub fn slope(&self) -> f64 {
+        20.0 * self.order() as f64
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttenuationPoint {
+    pub frequency: f64,
+    pub required: f64,  // dB insertion loss, measured - limit + margin
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterCandidate {
+    pub topology: FilterTopology,
+    pub order: u32,
+    pub slope: f64,                 // dB/decade
+    pub corner_frequency: f64,      // Hz, highest corner meeting every requirement
+    pub limiting_frequency: f64,    // Hz, the requirement that sets the corner
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterRequirement {
+    pub margin: f64,
+    pub attenuation: Vec<AttenuationPoint>,  // only frequencies needing attenuation
+    pub max_required_attenuation: f64,
+    pub max_required_frequency: f64,
+    pub candidates: Vec<FilterCandidate>,    // empty when the measurement already passes
+}
+
+// Highest corner frequency for which an ideal filter of the given slope provides the
+// required attenuation at every point, with the point that limits it
+pub fn max_corner_frequency(attenuation: &[AttenuationPoint], slope: f64) -> Option<(f64, f64)> {
+    attenuation
+        .iter()
+        .map(|point| (point.frequency * 10.0_f64.powf(-point.required / slope), point.frequency))
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+// Required insertion loss for a failing measurement and the LC / two-stage LC corners meeting it
+pub fn filter_requirement(
+    standard: &EMCStandard,
+    frequencies: &[f64],
+    amplitudes: &[f64],
+    detector: Detector,
+    margin: f64,
+) -> Result<FilterRequirement, EmcError> {
+    let compliance = standard.check_compliance(frequencies, amplitudes, detector)?;
+
+    let attenuation: Vec<AttenuationPoint> = compliance
+        .iter()
+        .filter(|point| point.frequency > 0.0)
+        .map(|point| AttenuationPoint {
+            frequency: point.frequency,
+            required: margin - point.margin,
+        })
+        .filter(|point| point.required > 0.0)
+        .collect();
+
+    let (max_required_attenuation, max_required_frequency) = attenuation
+        .iter()
+        .fold((0.0, 0.0), |acc, point| if point.required > acc.0 { (point.required, point.frequency) } else { acc });
+
+    let candidates = FilterTopology::ALL
+        .iter()
+        .filter_map(|&topology| {
+            let (corner_frequency, limiting_frequency) = max_corner_frequency(&attenuation, topology.slope())?;
+            Some(FilterCandidate {
+                topology,
+                order: topology.order(),
+                slope: topology.slope(),
+                corner_frequency,
+                limiting_frequency,
+            })
+        })
+        .collect();
+
+    Ok(FilterRequirement {
+        margin,
+        attenuation,
+        max_required_attenuation,
+        max_required_frequency,
+        candidates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_violation_corners() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let limit = standard.interp_log(1e6).dbuv_avg_limit;
+
+        // 10 dB over the limit plus 6 dB margin -> 16 dB needed at 1 MHz
+        let requirement = filter_requirement(&standard, &[1e6], &[limit + 10.0], Detector::Avg, 6.0).unwrap();
+        assert_eq!(requirement.attenuation.len(), 1);
+        assert!((requirement.max_required_attenuation - 16.0).abs() < 1e-9);
+
+        let lc = &requirement.candidates[0];
+        assert_eq!(lc.topology, FilterTopology::SingleStageLC);
+        assert!((lc.corner_frequency - 1e6 * 10.0_f64.powf(-16.0 / 40.0)).abs() < 1.0);
+
+        let two_stage = &requirement.candidates[1];
+        assert!((two_stage.corner_frequency - 1e6 * 10.0_f64.powf(-16.0 / 80.0)).abs() < 1.0);
+        assert!(two_stage.corner_frequency > lc.corner_frequency);
+    }
+
+    #[test]
+    fn test_limiting_frequency() {
+        let attenuation = vec![
+            AttenuationPoint { frequency: 200e3, required: 20.0 },
+            AttenuationPoint { frequency: 2e6, required: 30.0 },
+        ];
+        // 200 kHz needs fc <= 63 kHz, 2 MHz allows up to 355 kHz
+        let (corner, limiting) = max_corner_frequency(&attenuation, 40.0).unwrap();
+        assert_eq!(limiting, 200e3);
+        assert!((corner - 200e3 * 10.0_f64.powf(-0.5)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_passing_measurement_needs_no_filter() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let requirement = filter_requirement(&standard, &[1e6, 5e6], &[20.0, 20.0], Detector::Qp, 6.0).unwrap();
+
+        assert!(requirement.attenuation.is_empty());
+        assert!(requirement.candidates.is_empty());
+        assert_eq!(requirement.max_required_attenuation, 0.0);
+    }
+}
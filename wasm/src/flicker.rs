@@ -0,0 +1,322 @@
+// wasm/src/flicker.rs
+// Voltage fluctuations and flicker: IEC 61000-4-15 flickermeter on half-cycle RMS values, IEC 61000-3-3 limits
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::{ComplianceResult, EMCStandard, EmcError};
+
+const PST_LIMIT: f64 = 1.0;
+const PLT_LIMIT: f64 = 0.65;
+const DC_LIMIT: f64 = 3.3;      // %, also the d(t) threshold for Tmax
+const TMAX_LIMIT: f64 = 0.5;    // s
+
+const PST_PERIOD: f64 = 600.0;           // s, short-term observation period
+const PLT_PERIODS: usize = 12;           // short-term periods in a 2 h long-term period
+const STEADY_STATE_TOLERANCE: f64 = 0.2; // %, half-cycle RMS band for a steady-state voltage
+const STEADY_STATE_TIME: f64 = 1.0;      // s
+const MEAN_TIME_CONSTANT: f64 = 27.3;    // s, normalisation to the mean voltage
+const SENSATION_TIME_CONSTANT: f64 = 0.3; // s, block 4 sliding mean
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlickerOptions {
+    pub nominal_voltage: f64,  // V rms; below 180 V the 120 V lamp model is used
+    pub mains_frequency: f64,  // Hz, the series holds one RMS value per half cycle
+    pub dmax_limit: f64,       // %, 4 % in general, 6 % or 7 % for the conditional cases
+}
+
+impl Default for FlickerOptions {
+    fn default() -> Self {
+        FlickerOptions { nominal_voltage: 230.0, mains_frequency: 50.0, dmax_limit: 4.0 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlickerReport {
+    pub standard: String,
+    pub pst: Vec<f64>,    // one per 10 min observation period
+    pub plt: Option<f64>,
+    pub dc: f64,          // %, largest steady-state voltage change
+    pub dmax: f64,        // %, largest voltage change from the previous steady state
+    pub tmax: f64,        // s, longest time d(t) exceeds 3.3 % during one change
+    pub d: Vec<f64>,      // %, d(t) per half cycle, positive for voltage drops
+    pub checks: Vec<ComplianceResult>,  // one per quantity, amplitude = measured value
+    pub passes: bool,
+    pub notes: Vec<String>,
+}
+
+// Lamp-eye-brain weighting filter (IEC 61000-4-15 block 3):
+// K w1 s / (s² + 2 lambda s + w1²) * (1 + s/w2) / ((1 + s/w3)(1 + s/w4)),
+// with the 8.8 Hz sinusoidal fluctuation (peak to peak, %) that gives unit sensation
+struct Lamp {
+    k: f64,
+    lambda: f64,
+    w: [f64; 4],
+    reference: f64,
+}
+
+impl Lamp {
+    fn for_voltage(nominal_voltage: f64) -> Lamp {
+        let hz = |f: f64| 2.0 * PI * f;
+        if nominal_voltage < 180.0 {
+            Lamp { k: 1.6357, lambda: hz(4.167375), w: [hz(9.077169), hz(2.939902), hz(1.394468), hz(17.31512)], reference: 0.321 }
+        } else {
+            Lamp { k: 1.74802, lambda: hz(4.05981), w: [hz(9.15494), hz(2.27979), hz(1.22535), hz(21.9)], reference: 0.250 }
+        }
+    }
+}
+
+// Second-order section, transposed direct form II
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 3],
+    z: [f64; 2],
+}
+
+impl Biquad {
+    // Bilinear transform of (b0 + b1 s + b2 s²) / (a0 + a1 s + a2 s²)
+    fn bilinear(b: [f64; 3], a: [f64; 3], sample_rate: f64) -> Biquad {
+        let c = 2.0 * sample_rate;
+        let map = |p: [f64; 3]| [p[0] + p[1] * c + p[2] * c * c, 2.0 * (p[0] - p[2] * c * c), p[0] - p[1] * c + p[2] * c * c];
+        let (bz, az) = (map(b), map(a));
+        Biquad { b: bz.map(|v| v / az[0]), a: az.map(|v| v / az[0]), z: [0.0; 2] }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[1] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[2] * y;
+        y
+    }
+
+    fn gain(&self, frequency: f64, sample_rate: f64) -> f64 {
+        let w = 2.0 * PI * frequency / sample_rate;
+        let magnitude = |c: &[f64; 3]| {
+            let (re, im) = (0..3).fold((0.0, 0.0), |(re, im), k| (re + c[k] * (w * k as f64).cos(), im - c[k] * (w * k as f64).sin()));
+            re.hypot(im)
+        };
+        magnitude(&self.b) / magnitude(&self.a)
+    }
+}
+
+// Instantaneous flicker sensation S(t), one value per half cycle (blocks 2-4)
+fn instantaneous_flicker(series: &[f64], sample_rate: f64, lamp: &Lamp) -> Vec<f64> {
+    // Prewarp the corner frequencies so the bilinear transform keeps them in place
+    let warp = |w: f64| 2.0 * sample_rate * (w / (2.0 * sample_rate)).tan();
+    let [w1, w2, w3, w4] = lamp.w.map(warp);
+    let lambda = lamp.lambda * w1 / lamp.w[0];
+    let mut chain = [
+        Biquad::bilinear([0.0, 1.0, 0.0], [2.0 * PI * 0.05, 1.0, 0.0], sample_rate),  // 0.05 Hz high-pass
+        Biquad::bilinear([0.0, lamp.k * w1, 0.0], [w1 * w1, 2.0 * lambda, 1.0], sample_rate),
+        Biquad::bilinear([1.0, 1.0 / w2, 0.0], [1.0, 1.0 / w3 + 1.0 / w4, 1.0 / (w3 * w4)], sample_rate),
+    ];
+    let mut smoothing = Biquad::bilinear([1.0, 0.0, 0.0], [1.0, SENSATION_TIME_CONSTANT, 0.0], sample_rate);
+
+    // Squaring the relative RMS doubles a small fluctuation: amplitude 2m after demodulation,
+    // so the reference fluctuation (m = reference / 2) must square-average to one
+    let gain: f64 = chain.iter().map(|f| f.gain(8.8, sample_rate)).product();
+    let m = lamp.reference / 200.0;
+    let scale = 1.0 / (2.0 * m * m * gain * gain);
+
+    let alpha = 1.0 - (-1.0 / (sample_rate * MEAN_TIME_CONSTANT)).exp();
+    let mut mean = series[0];
+    series
+        .iter()
+        .map(|&v| {
+            mean += alpha * (v - mean);
+            let x = (v / mean).powi(2) - 1.0;
+            let y = chain.iter_mut().fold(x, |x, f| f.process(x));
+            scale * smoothing.process(y * y)
+        })
+        .collect()
+}
+
+// Short-term flicker severity from the cumulative probability of S(t) (block 5)
+fn short_term_severity(sensation: &[f64]) -> f64 {
+    let mut sorted = sensation.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    // Level exceeded for x % of the time
+    let p = |x: f64| EMCStandard::calculate_percentile(&sorted, 100.0 - x);
+
+    let p1s = (p(0.7) + p(1.0) + p(1.5)) / 3.0;
+    let p3s = (p(2.2) + p(3.0) + p(4.0)) / 3.0;
+    let p10s = (p(6.0) + p(8.0) + p(10.0) + p(13.0) + p(17.0)) / 5.0;
+    let p50s = (p(30.0) + p(50.0) + p(80.0)) / 3.0;
+
+    (0.0314 * p(0.1) + 0.0525 * p1s + 0.0657 * p3s + 0.28 * p10s + 0.08 * p50s).max(0.0).sqrt()
+}
+
+pub fn long_term_severity(pst: &[f64]) -> f64 {
+    (pst.iter().map(|p| p.powi(3)).sum::<f64>() / pst.len() as f64).cbrt()
+}
+
+struct VoltageChanges {
+    d: Vec<f64>,
+    dc: f64,
+    dmax: f64,
+    tmax: f64,
+}
+
+// d(t) relative to the last steady-state voltage; a steady state holds for 1 s within the tolerance band
+fn voltage_changes(series: &[f64], nominal_voltage: f64, sample_rate: f64) -> VoltageChanges {
+    let window = ((STEADY_STATE_TIME * sample_rate).round() as usize).max(1);
+    let tolerance = STEADY_STATE_TOLERANCE / 100.0 * nominal_voltage;
+
+    let mut changes = VoltageChanges { d: Vec::with_capacity(series.len()), dc: 0.0, dmax: 0.0, tmax: 0.0 };
+    let mut reference = series[0];
+    let mut in_change = false;
+    let mut above = 0usize;
+
+    for (i, &v) in series.iter().enumerate() {
+        let d = 100.0 * (reference - v) / nominal_voltage;
+        changes.d.push(d);
+        if i + 1 < window {
+            continue;
+        }
+
+        let recent = &series[i + 1 - window..=i];
+        let (lo, hi) = recent.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+        if hi - lo <= tolerance {
+            let level = recent.iter().sum::<f64>() / window as f64;
+            if in_change {
+                changes.dc = changes.dc.max(100.0 * (reference - level).abs() / nominal_voltage);
+                in_change = false;
+            }
+            reference = level;
+        } else {
+            if !in_change {
+                in_change = true;
+                above = 0;
+            }
+            changes.dmax = changes.dmax.max(d.abs());
+            if d.abs() > DC_LIMIT {
+                above += 1;
+                changes.tmax = changes.tmax.max(above as f64 / sample_rate);
+            }
+        }
+    }
+
+    changes
+}
+
+// Flicker evaluation of a half-cycle RMS voltage series against IEC 61000-3-3
+pub fn evaluate_flicker(half_cycle_rms: &[f64], options: &FlickerOptions) -> Result<FlickerReport, EmcError> {
+    if !(options.nominal_voltage > 0.0 && options.mains_frequency > 0.0 && options.dmax_limit > 0.0) {
+        return Err(EmcError::invalid("Nominal voltage, mains frequency and dmax limit must be positive"));
+    }
+    let sample_rate = 2.0 * options.mains_frequency;
+    if (half_cycle_rms.len() as f64) < STEADY_STATE_TIME * sample_rate {
+        return Err(EmcError::invalid("Need at least one second of half-cycle RMS values"));
+    }
+    if let Some(i) = half_cycle_rms.iter().position(|v| !v.is_finite() || *v <= 0.0) {
+        return Err(EmcError::invalid(format!("Half-cycle RMS value {} must be positive", i)));
+    }
+
+    let mut notes = Vec::new();
+    let lamp = Lamp::for_voltage(options.nominal_voltage);
+    let sensation = instantaneous_flicker(half_cycle_rms, sample_rate, &lamp);
+
+    let period = (PST_PERIOD * sample_rate).round() as usize;
+    let pst: Vec<f64> = if sensation.len() < period {
+        notes.push(format!("Observation of {:.0} s is shorter than the 10 min Pst period", sensation.len() as f64 / sample_rate));
+        vec![short_term_severity(&sensation)]
+    } else {
+        if !sensation.len().is_multiple_of(period) {
+            notes.push("Trailing partial 10 min period ignored".to_string());
+        }
+        sensation.chunks_exact(period).map(short_term_severity).collect()
+    };
+
+    let plt = (pst.len() > 1).then(|| long_term_severity(&pst));
+    if plt.is_some() && pst.len() < PLT_PERIODS {
+        notes.push(format!("Plt from {} Pst values instead of {}", pst.len(), PLT_PERIODS));
+    }
+
+    let changes = voltage_changes(half_cycle_rms, options.nominal_voltage, sample_rate);
+    let worst_pst = pst.iter().cloned().fold(0.0, f64::max);
+
+    let mut checks = vec![ComplianceResult::for_quantity("pst", worst_pst, PST_LIMIT)];
+    if let Some(plt) = plt {
+        checks.push(ComplianceResult::for_quantity("plt", plt, PLT_LIMIT));
+    }
+    checks.push(ComplianceResult::for_quantity("dc", changes.dc, DC_LIMIT));
+    checks.push(ComplianceResult::for_quantity("dmax", changes.dmax, options.dmax_limit));
+    checks.push(ComplianceResult::for_quantity("tmax", changes.tmax, TMAX_LIMIT));
+
+    Ok(FlickerReport {
+        standard: "IEC 61000-3-3".to_string(),
+        pst,
+        plt,
+        dc: changes.dc,
+        dmax: changes.dmax,
+        tmax: changes.tmax,
+        d: changes.d,
+        passes: checks.iter().all(|c| c.passes),
+        checks,
+        notes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Rectangular fluctuation of dv_percent (peak to peak) at the given changes per minute
+    fn rectangular(nominal: f64, dv_percent: f64, changes_per_minute: f64, seconds: f64) -> Vec<f64> {
+        let half_cycles_per_change = 6000.0 / changes_per_minute;
+        (0..(seconds * 100.0) as usize)
+            .map(|i| {
+                let high = ((i as f64 / half_cycles_per_change).floor() as usize).is_multiple_of(2);
+                nominal * (1.0 + if high { 0.5 } else { -0.5 } * dv_percent / 100.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sinusoidal_reference_gives_unit_sensation() {
+        let lamp = Lamp::for_voltage(230.0);
+        let series: Vec<f64> = (0..6000).map(|i| 230.0 * (1.0 + 0.00125 * (2.0 * PI * 8.8 * i as f64 / 100.0).sin())).collect();
+        let sensation = instantaneous_flicker(&series, 100.0, &lamp);
+        let settled = &sensation[3000..];
+        let mean = settled.iter().sum::<f64>() / settled.len() as f64;
+        assert!((mean - 1.0).abs() < 0.02, "mean S = {}", mean);
+    }
+
+    #[test]
+    fn test_rectangular_pst_calibration_points() {
+        // IEC 61000-4-15 table: these rectangular fluctuations give Pst = 1 on the 230 V lamp
+        for &(cpm, dv) in &[(39.0, 0.906), (110.0, 0.725), (1620.0, 0.402)] {
+            let report = evaluate_flicker(&rectangular(230.0, dv, cpm, 600.0), &FlickerOptions::default()).unwrap();
+            assert_eq!(report.pst.len(), 1);
+            assert!((report.pst[0] - 1.0).abs() < 0.05, "{} cpm: Pst = {}", cpm, report.pst[0]);
+        }
+    }
+
+    #[test]
+    fn test_voltage_changes() {
+        // 230 V, a 5 % dip for 300 ms every 10 s: dmax 5 %, Tmax 0.3 s, no steady-state change
+        let series: Vec<f64> = (0..6000).map(|i| if i % 1000 >= 500 && i % 1000 < 530 { 218.5 } else { 230.0 }).collect();
+        let report = evaluate_flicker(&series, &FlickerOptions::default()).unwrap();
+        assert!((report.dmax - 5.0).abs() < 1e-9);
+        assert!((report.tmax - 0.3).abs() < 1e-9);
+        assert!(report.dc < 1e-9);
+        assert!(!report.passes);
+        let dmax = report.checks.iter().find(|c| c.quantity.as_deref() == Some("dmax")).unwrap();
+        assert!(!dmax.passes);
+        assert!(report.checks.iter().find(|c| c.quantity.as_deref() == Some("tmax")).unwrap().passes);
+
+        // A permanent 2 % step is a steady-state change
+        let step: Vec<f64> = (0..600).map(|i| if i < 300 { 230.0 } else { 225.4 }).collect();
+        let report = evaluate_flicker(&step, &FlickerOptions::default()).unwrap();
+        assert!((report.dc - 2.0).abs() < 1e-9);
+        assert!(report.notes.iter().any(|n| n.contains("shorter")));
+    }
+
+    #[test]
+    fn test_plt_and_validation() {
+        assert!((long_term_severity(&[1.0; 12]) - 1.0).abs() < 1e-12);
+        assert!((long_term_severity(&[2.0, 0.0]) - 4.0_f64.cbrt()).abs() < 1e-12);
+        assert!(evaluate_flicker(&[230.0; 50], &FlickerOptions::default()).is_err());
+        assert!(evaluate_flicker(&[0.0; 500], &FlickerOptions::default()).is_err());
+    }
+}
@@ -9,6 +9,7 @@ pub const GHZ: f64 = 1e9;
 // EMC frequency bands
 pub mod frequency_bands {
     use super::*;
+    use serde::{Deserialize, Serialize};
     
     // Standard EMC frequency ranges
     pub const LF_START: f64 = 150.0 * KHZ;           // 150 kHz
@@ -22,12 +23,97 @@ pub mod frequency_bands {
     pub const CISPR_BREAK_1: f64 = 500.0 * KHZ;      // 500 kHz
     pub const CISPR_BREAK_2: f64 = 5.0 * MHZ;        // 5 MHz
     pub const CISPR_BREAK_3: f64 = 30.0 * MHZ;       // 30 MHz
+
+    // CISPR 16-1-1 measurement bands
+    pub const CISPR_BAND_A_START: f64 = 9.0 * KHZ;   // 9 kHz
+    pub const CISPR_BAND_A_END: f64 = 150.0 * KHZ;   // 150 kHz
+    pub const CISPR_BAND_B_START: f64 = 150.0 * KHZ; // 150 kHz
+    pub const CISPR_BAND_B_END: f64 = 30.0 * MHZ;    // 30 MHz
+    pub const CISPR_BAND_C_START: f64 = 30.0 * MHZ;  // 30 MHz
+    pub const CISPR_BAND_C_END: f64 = 300.0 * MHZ;   // 300 MHz
+    pub const CISPR_BAND_D_START: f64 = 300.0 * MHZ; // 300 MHz
+    pub const CISPR_BAND_D_END: f64 = 1.0 * GHZ;     // 1 GHz
+    pub const CISPR_BAND_E_START: f64 = 1.0 * GHZ;   // 1 GHz
+    pub const CISPR_BAND_E_END: f64 = 18.0 * GHZ;    // 18 GHz
+
+    // CISPR 16-1-1 measurement bands with their receiver settings
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    pub enum CisprBand {
+        A,
+        B,
+        C,
+        D,
+        E,
+    }
+
+    impl CisprBand {
+        pub const ALL: [CisprBand; 5] = [CisprBand::A, CisprBand::B, CisprBand::C, CisprBand::D, CisprBand::E];
+
+        // Frequency range of the band (start_hz, end_hz)
+        pub fn range(&self) -> (f64, f64) {
+            match self {
+                CisprBand::A => (CISPR_BAND_A_START, CISPR_BAND_A_END),
+                CisprBand::B => (CISPR_BAND_B_START, CISPR_BAND_B_END),
+                CisprBand::C => (CISPR_BAND_C_START, CISPR_BAND_C_END),
+                CisprBand::D => (CISPR_BAND_D_START, CISPR_BAND_D_END),
+                CisprBand::E => (CISPR_BAND_E_START, CISPR_BAND_E_END),
+            }
+        }
+
+        // Band containing the frequency; shared edges belong to the lower band
+        pub fn from_frequency(frequency: f64) -> Option<CisprBand> {
+            Self::ALL.iter().copied().find(|band| {
+                let (start, end) = band.range();
+                frequency >= start && frequency <= end
+            })
+        }
+
+        // 6 dB resolution bandwidth (Hz)
+        pub fn rbw(&self) -> f64 {
+            match self {
+                CisprBand::A => 200.0,
+                CisprBand::B => 9.0 * KHZ,
+                CisprBand::C | CisprBand::D => 120.0 * KHZ,
+                CisprBand::E => 1.0 * MHZ,
+            }
+        }
+
+        // CISPR-average meter time constant (s)
+        pub fn meter_time_constant(&self) -> f64 {
+            match self {
+                CisprBand::A | CisprBand::B => 0.160,
+                CisprBand::C | CisprBand::D | CisprBand::E => 0.100,
+            }
+        }
+
+        // Quasi-peak detection is only defined up to 1 GHz
+        pub fn has_quasi_peak(&self) -> bool {
+            !matches!(self, CisprBand::E)
+        }
+
+        // CISPR 16-1-1 quasi-peak detector time constants
+        pub fn qp_time_constants(&self) -> Option<QuasiPeakTimeConstants> {
+            match self {
+                CisprBand::A => Some(QuasiPeakTimeConstants { charge: 0.045, discharge: 0.500, meter: 0.160 }),
+                CisprBand::B => Some(QuasiPeakTimeConstants { charge: 0.001, discharge: 0.160, meter: 0.160 }),
+                CisprBand::C | CisprBand::D => Some(QuasiPeakTimeConstants { charge: 0.001, discharge: 0.550, meter: 0.100 }),
+                CisprBand::E => None,
+            }
+        }
+    }
+
+    // Electrical charge/discharge and mechanical meter time constants (s)
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    pub struct QuasiPeakTimeConstants {
+        pub charge: f64,
+        pub discharge: f64,
+        pub meter: f64,
+    }
 }
 
 // Example of how to define standards with expressions in Rust
 pub mod standard_definitions {
     use super::*;
-    use super::frequency_bands::*;
     
     // CISPR 22 Class A with expression-based frequencies
     pub fn cispr22_class_a_frequencies() -> Vec<f64> {
@@ -114,4 +200,21 @@ mod tests {
             assert!(frequencies[i] > frequencies[i-1]);
         }
     }
+
+    #[test]
+    fn test_cispr_band_lookup() {
+        use super::frequency_bands::CisprBand;
+
+        assert_eq!(CisprBand::from_frequency(50.0 * KHZ), Some(CisprBand::A));
+        assert_eq!(CisprBand::from_frequency(150.0 * KHZ), Some(CisprBand::A));
+        assert_eq!(CisprBand::from_frequency(1.0 * MHZ), Some(CisprBand::B));
+        assert_eq!(CisprBand::from_frequency(100.0 * MHZ), Some(CisprBand::C));
+        assert_eq!(CisprBand::from_frequency(500.0 * MHZ), Some(CisprBand::D));
+        assert_eq!(CisprBand::from_frequency(6.0 * GHZ), Some(CisprBand::E));
+        assert_eq!(CisprBand::from_frequency(1.0 * KHZ), None);
+
+        assert_eq!(CisprBand::B.rbw(), 9_000.0);
+        assert_eq!(CisprBand::C.rbw(), CisprBand::D.rbw());
+        assert!(!CisprBand::E.has_quasi_peak());
+    }
 }
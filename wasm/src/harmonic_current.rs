@@ -0,0 +1,431 @@
+// wasm/src/harmonic_current.rs
+// Mains harmonic current emissions: IEC 61000-3-2 (<= 16 A) and IEC 61000-3-12 (16 A - 75 A)
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::EmcError;
+
+pub const MAX_ORDER: usize = 40;
+
+// IEC 61000-3-2 allows odd harmonics 21..39 to exceed their limit by this factor
+// when the partial odd harmonic current stays within the limit-derived POHC
+const POHC_ALLOWANCE: f64 = 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EquipmentClass {
+    A,  // balanced three-phase, household appliances, tools, dimmers, audio
+    B,  // portable tools, non-professional arc welding
+    C,  // lighting equipment
+    D,  // PCs, monitors, TV receivers (75 W < P <= 600 W)
+}
+
+impl EquipmentClass {
+    pub fn parse(name: &str) -> Result<EquipmentClass, EmcError> {
+        match name.to_lowercase().trim_start_matches("class").trim() {
+            "a" => Ok(EquipmentClass::A),
+            "b" => Ok(EquipmentClass::B),
+            "c" => Ok(EquipmentClass::C),
+            "d" => Ok(EquipmentClass::D),
+            _ => Err(EmcError::invalid("Equipment class must be 'A', 'B', 'C' or 'D'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarmonicLimitResult {
+    pub order: usize,
+    pub current: f64,  // A rms
+    pub limit: f64,    // A rms, NaN when the order is not individually limited
+    pub ratio: f64,    // current / limit
+    pub passes: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarmonicCurrentReport {
+    pub standard: String,
+    pub fundamental: f64,          // A rms
+    pub thd: f64,                  // %, harmonics 2..40 over the fundamental
+    pub thc: f64,                  // A, total harmonic current
+    pub pohc: f64,                 // A, partial odd harmonic current (21..39)
+    pub pwhc: f64,                 // A, partial weighted harmonic current (14..40)
+    pub thc_limit: Option<f64>,    // A
+    pub pohc_limit: Option<f64>,   // A
+    pub pwhc_limit: Option<f64>,   // A
+    pub harmonics: Vec<HarmonicLimitResult>,
+    pub passes: bool,
+    pub notes: Vec<String>,
+}
+
+// IEC 61000-3-2 Table 1, Class A maximum permissible harmonic current (A)
+fn class_a_limit(order: usize) -> f64 {
+    match order {
+        2 => 1.08,
+        3 => 2.30,
+        4 => 0.43,
+        5 => 1.14,
+        6 => 0.30,
+        7 => 0.77,
+        9 => 0.40,
+        11 => 0.33,
+        13 => 0.21,
+        n if n % 2 == 1 => 0.15 * 15.0 / n as f64,
+        n => 0.23 * 8.0 / n as f64,
+    }
+}
+
+// IEC 61000-3-2 Table 3, Class D limits per watt (mA/W); odd orders only
+fn class_d_per_watt(order: usize) -> f64 {
+    match order {
+        3 => 3.4,
+        5 => 1.9,
+        7 => 1.0,
+        9 => 0.5,
+        11 => 0.35,
+        n if n % 2 == 1 => 3.85 / n as f64,
+        _ => f64::NAN,
+    }
+}
+
+// IEC 61000-3-2 Table 2, Class C (> 25 W) limits as % of the fundamental
+fn class_c_percent(order: usize, power_factor: f64) -> f64 {
+    match order {
+        2 => 2.0,
+        3 => 30.0 * power_factor,
+        5 => 10.0,
+        7 => 7.0,
+        9 => 5.0,
+        n if n % 2 == 1 => 3.0,
+        _ => f64::NAN,
+    }
+}
+
+// Summary quantities of a harmonic current set: (thd %, thc, pohc, pwhc)
+fn summary(harmonics: &[f64]) -> (f64, f64, f64, f64) {
+    let at = |n: usize| harmonics.get(n - 1).copied().unwrap_or(0.0);
+    let thc = (2..=MAX_ORDER).map(|n| at(n).powi(2)).sum::<f64>().sqrt();
+    let pohc = (21..=39).step_by(2).map(|n| at(n).powi(2)).sum::<f64>().sqrt();
+    let pwhc = (14..=MAX_ORDER).map(|n| n as f64 * at(n).powi(2)).sum::<f64>().sqrt();
+    let thd = if at(1) > 0.0 { 100.0 * thc / at(1) } else { f64::NAN };
+    (thd, thc, pohc, pwhc)
+}
+
+fn validate(harmonics: &[f64]) -> Result<(), EmcError> {
+    if harmonics.len() < 2 {
+        return Err(EmcError::invalid("Need the fundamental and at least one harmonic (index 0 = fundamental)"));
+    }
+    if let Some(n) = harmonics.iter().position(|h| !h.is_finite() || *h < 0.0) {
+        return Err(EmcError::invalid(format!("Harmonic {} must be a non-negative rms current", n + 1)));
+    }
+    Ok(())
+}
+
+fn evaluate(order: usize, current: f64, limit: f64) -> HarmonicLimitResult {
+    HarmonicLimitResult {
+        order,
+        current,
+        limit,
+        ratio: current / limit,
+        passes: limit.is_nan() || current <= limit,
+    }
+}
+
+// Per-harmonic RMS currents (index 0 = fundamental) from a sampled current waveform.
+// Uses the largest whole number of fundamental cycles in the record.
+pub fn harmonics_from_waveform(samples: &[f64], sample_rate: f64, fundamental_frequency: f64) -> Result<Vec<f64>, EmcError> {
+    if !sample_rate.is_finite() || sample_rate <= 0.0 || !fundamental_frequency.is_finite() || fundamental_frequency <= 0.0 {
+        return Err(EmcError::invalid("Sample rate and fundamental frequency must be positive"));
+    }
+    if sample_rate < 2.0 * MAX_ORDER as f64 * fundamental_frequency {
+        return Err(EmcError::invalid(format!("Sample rate must exceed {} Hz to resolve harmonic {}", 2.0 * MAX_ORDER as f64 * fundamental_frequency, MAX_ORDER)));
+    }
+
+    let samples_per_cycle = sample_rate / fundamental_frequency;
+    let cycles = (samples.len() as f64 / samples_per_cycle).floor();
+    if cycles < 1.0 {
+        return Err(EmcError::invalid("Record must contain at least one fundamental cycle"));
+    }
+    let n = (cycles * samples_per_cycle).round() as usize;
+    let window = &samples[..n.min(samples.len())];
+    let len = window.len() as f64;
+
+    Ok((1..=MAX_ORDER)
+        .map(|order| {
+            let omega = 2.0 * PI * order as f64 * fundamental_frequency / sample_rate;
+            let (re, im) = window.iter().enumerate().fold((0.0, 0.0), |(re, im), (k, &x)| {
+                let (sin, cos) = (omega * k as f64).sin_cos();
+                (re + x * cos, im - x * sin)
+            });
+            // Peak amplitude 2|X|/N, reported as rms
+            2.0 * re.hypot(im) / len / 2.0_f64.sqrt()
+        })
+        .collect())
+}
+
+// IEC 61000-3-2 check. Class C needs active power and power factor, class D active power (W).
+pub fn check_iec61000_3_2(
+    harmonics: &[f64],
+    class: EquipmentClass,
+    active_power: Option<f64>,
+    power_factor: Option<f64>,
+) -> Result<HarmonicCurrentReport, EmcError> {
+    validate(harmonics)?;
+    let fundamental = harmonics[0];
+    let mut notes = Vec::new();
+
+    let limit_for: Box<dyn Fn(usize) -> f64> = match class {
+        EquipmentClass::A => Box::new(class_a_limit),
+        EquipmentClass::B => Box::new(|n| 1.5 * class_a_limit(n)),
+        EquipmentClass::C => {
+            let power = active_power.ok_or_else(|| EmcError::invalid("Class C limits depend on the active input power"))?;
+            if power > 25.0 {
+                let lambda = power_factor.ok_or_else(|| EmcError::invalid("Class C limits above 25 W depend on the circuit power factor"))?;
+                Box::new(move |n| class_c_percent(n, lambda) / 100.0 * fundamental)
+            } else {
+                notes.push("Class C <= 25 W: using the Class D per-watt limits".to_string());
+                Box::new(move |n| class_d_per_watt(n) * power / 1000.0)
+            }
+        }
+        EquipmentClass::D => {
+            let power = active_power.ok_or_else(|| EmcError::invalid("Class D limits depend on the active input power"))?;
+            if power > 600.0 {
+                notes.push("Class D above 600 W: Class A limits apply".to_string());
+                Box::new(class_a_limit)
+            } else if power <= 75.0 {
+                notes.push("Class D at or below 75 W: no limits apply".to_string());
+                Box::new(|_| f64::NAN)
+            } else {
+                // Capped at Class A; even orders have no Class D limit (NaN.min would pick Class A)
+                Box::new(move |n| {
+                    let limit = class_d_per_watt(n) * power / 1000.0;
+                    if limit.is_nan() { limit } else { limit.min(class_a_limit(n)) }
+                })
+            }
+        }
+    };
+
+    let (thd, thc, pohc, pwhc) = summary(harmonics);
+    let limits: Vec<f64> = (2..=harmonics.len().min(MAX_ORDER)).map(&limit_for).collect();
+
+    // POHC allowance for odd orders 21..39 (not for lighting equipment)
+    let pohc_limit = (class != EquipmentClass::C).then(|| {
+        (21..=39).step_by(2).map(&limit_for).filter(|l| !l.is_nan()).map(|l| l * l).sum::<f64>().sqrt()
+    });
+    let allowance = pohc_limit.is_some_and(|l| l > 0.0 && pohc <= l);
+    if allowance {
+        notes.push("Odd harmonics 21-39 may exceed their limits by 50% (POHC within limit)".to_string());
+    }
+
+    let results: Vec<HarmonicLimitResult> = limits
+        .iter()
+        .enumerate()
+        .map(|(i, &limit)| {
+            let order = i + 2;
+            let limit = if allowance && order >= 21 && order % 2 == 1 { limit * POHC_ALLOWANCE } else { limit };
+            evaluate(order, harmonics[order - 1], limit)
+        })
+        .collect();
+
+    Ok(HarmonicCurrentReport {
+        standard: format!("IEC 61000-3-2 Class {:?}", class),
+        fundamental,
+        thd,
+        thc,
+        pohc,
+        pwhc,
+        thc_limit: None,
+        pohc_limit: pohc_limit.filter(|l| *l > 0.0),
+        pwhc_limit: None,
+        passes: results.iter().all(|r| r.passes),
+        harmonics: results,
+        notes,
+    })
+}
+
+// IEC 61000-3-12 Tables 2 and 3: (Rsce, odd harmonic limits % for orders, THC %, PWHC %)
+const TABLE_SINGLE_PHASE: [(f64, [f64; 6], f64, f64); 5] = [
+    (33.0, [21.6, 10.7, 7.2, 3.8, 3.1, 2.0], 23.0, 23.0),
+    (66.0, [24.0, 13.0, 8.0, 5.0, 4.0, 3.0], 26.0, 26.0),
+    (120.0, [27.0, 15.0, 10.0, 6.0, 5.0, 4.0], 30.0, 30.0),
+    (250.0, [35.0, 20.0, 13.0, 9.0, 8.0, 6.0], 40.0, 40.0),
+    (350.0, [41.0, 24.0, 15.0, 12.0, 10.0, 8.0], 47.0, 47.0),
+];
+const SINGLE_PHASE_ORDERS: [usize; 6] = [3, 5, 7, 9, 11, 13];
+
+const TABLE_BALANCED: [(f64, [f64; 4], f64, f64); 5] = [
+    (33.0, [10.7, 7.2, 3.1, 2.0], 13.0, 22.0),
+    (66.0, [14.0, 9.0, 5.0, 3.0], 16.0, 25.0),
+    (120.0, [19.0, 12.0, 7.0, 4.0], 22.0, 28.0),
+    (250.0, [31.0, 20.0, 12.0, 7.0], 37.0, 38.0),
+    (350.0, [40.0, 25.0, 15.0, 10.0], 48.0, 46.0),
+];
+const BALANCED_ORDERS: [usize; 4] = [5, 7, 11, 13];
+
+// IEC 61000-3-12 check against the table row for the largest tabulated Rsce not above `rsce`.
+// Limits are relative to the reference current (rated fundamental, defaults to the measured one).
+pub fn check_iec61000_3_12(
+    harmonics: &[f64],
+    rsce: f64,
+    balanced_three_phase: bool,
+    reference_current: Option<f64>,
+) -> Result<HarmonicCurrentReport, EmcError> {
+    validate(harmonics)?;
+    if !rsce.is_finite() || rsce < 33.0 {
+        return Err(EmcError::invalid("IEC 61000-3-12 needs a short-circuit ratio Rsce of at least 33"));
+    }
+
+    let fundamental = harmonics[0];
+    let reference = reference_current.unwrap_or(fundamental);
+    if reference <= 0.0 {
+        return Err(EmcError::invalid("Reference current must be positive"));
+    }
+
+    let (row_rsce, odd_limits, thc_pct, pwhc_pct): (f64, Vec<(usize, f64)>, f64, f64) = if balanced_three_phase {
+        let row = TABLE_BALANCED.iter().rev().find(|row| row.0 <= rsce).unwrap_or(&TABLE_BALANCED[0]);
+        (row.0, BALANCED_ORDERS.iter().cloned().zip(row.1.iter().cloned()).collect(), row.2, row.3)
+    } else {
+        let row = TABLE_SINGLE_PHASE.iter().rev().find(|row| row.0 <= rsce).unwrap_or(&TABLE_SINGLE_PHASE[0]);
+        (row.0, SINGLE_PHASE_ORDERS.iter().cloned().zip(row.1.iter().cloned()).collect(), row.2, row.3)
+    };
+
+    let percent_limit = |order: usize| -> f64 {
+        if let Some(&(_, pct)) = odd_limits.iter().find(|(n, _)| *n == order) {
+            pct
+        } else if order.is_multiple_of(2) && order <= 12 {
+            16.0 / order as f64
+        } else {
+            f64::NAN  // covered by THC / PWHC only
+        }
+    };
+
+    let (thd, thc, pohc, pwhc) = summary(harmonics);
+    let thc_limit = thc_pct / 100.0 * reference;
+    let pwhc_limit = pwhc_pct / 100.0 * reference;
+
+    let results: Vec<HarmonicLimitResult> = (2..=harmonics.len().min(MAX_ORDER))
+        .map(|order| evaluate(order, harmonics[order - 1], percent_limit(order) / 100.0 * reference))
+        .collect();
+
+    let mut notes = vec![format!("Limits for Rsce = {}", row_rsce)];
+    if thc > thc_limit {
+        notes.push("Total harmonic current exceeds its limit".to_string());
+    }
+    if pwhc > pwhc_limit {
+        notes.push("Partial weighted harmonic current exceeds its limit".to_string());
+    }
+
+    Ok(HarmonicCurrentReport {
+        standard: format!(
+            "IEC 61000-3-12 {}",
+            if balanced_three_phase { "balanced three-phase" } else { "other than balanced three-phase" }
+        ),
+        fundamental,
+        thd,
+        thc,
+        pohc,
+        pwhc,
+        thc_limit: Some(thc_limit),
+        pohc_limit: None,
+        pwhc_limit: Some(pwhc_limit),
+        passes: results.iter().all(|r| r.passes) && thc <= thc_limit && pwhc <= pwhc_limit,
+        harmonics: results,
+        notes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spectrum(fundamental: f64, harmonics: &[(usize, f64)]) -> Vec<f64> {
+        let mut h = vec![0.0; MAX_ORDER];
+        h[0] = fundamental;
+        for &(n, value) in harmonics {
+            h[n - 1] = value;
+        }
+        h
+    }
+
+    #[test]
+    fn test_class_a_and_b_limits() {
+        assert_eq!(class_a_limit(3), 2.30);
+        assert!((class_a_limit(15) - 0.15).abs() < 1e-12);
+        assert!((class_a_limit(40) - 0.046).abs() < 1e-12);
+
+        let h = spectrum(10.0, &[(3, 2.5), (5, 1.0)]);
+        let a = check_iec61000_3_2(&h, EquipmentClass::A, None, None).unwrap();
+        assert!(!a.passes);
+        assert!(!a.harmonics[1].passes);
+        // Class B: 1.5 x Class A, 3.45 A for the 3rd
+        let b = check_iec61000_3_2(&h, EquipmentClass::B, None, None).unwrap();
+        assert!(b.passes);
+        assert!((b.harmonics[1].limit - 3.45).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_power_dependent_classes() {
+        // Class D at 200 W: 3rd limited to 3.4 mA/W * 200 W = 0.68 A
+        let h = spectrum(1.0, &[(3, 0.7), (5, 0.3)]);
+        let d = check_iec61000_3_2(&h, EquipmentClass::D, Some(200.0), None).unwrap();
+        assert!((d.harmonics[1].limit - 0.68).abs() < 1e-12);
+        assert!(!d.harmonics[1].passes);
+        assert!(d.harmonics[0].limit.is_nan() && d.harmonics[2].limit.is_nan());  // 2nd and 4th: odd orders only
+        assert!(check_iec61000_3_2(&h, EquipmentClass::D, None, None).is_err());
+
+        // Class C above 25 W: 3rd = 30 % x power factor of the fundamental
+        let c = check_iec61000_3_2(&h, EquipmentClass::C, Some(60.0), Some(0.95)).unwrap();
+        assert!((c.harmonics[1].limit - 0.285).abs() < 1e-12);
+        assert!(c.harmonics[0].limit == 0.02);
+    }
+
+    #[test]
+    fn test_pohc_allowance() {
+        // 21st at 120 % of its Class A limit, nothing else above 21: POHC stays within limit
+        let limit_21 = class_a_limit(21);
+        let h = spectrum(5.0, &[(21, 1.2 * limit_21)]);
+        let report = check_iec61000_3_2(&h, EquipmentClass::A, None, None).unwrap();
+
+        assert!(report.pohc <= report.pohc_limit.unwrap());
+        assert!(report.passes);
+        assert!((report.harmonics[19].limit - 1.5 * limit_21).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_waveform_decomposition() {
+        let (f0, fs) = (50.0, 20_000.0);
+        let samples: Vec<f64> = (0..2_000)
+            .map(|k| {
+                let t = k as f64 / fs;
+                let w = 2.0 * PI * f0 * t;
+                2.0_f64.sqrt() * (10.0 * w.sin() + 3.0 * (3.0 * w).sin() + 1.0 * (5.0 * w + 0.3).cos())
+            })
+            .collect();
+        let h = harmonics_from_waveform(&samples, fs, f0).unwrap();
+
+        assert!((h[0] - 10.0).abs() < 1e-9);
+        assert!((h[2] - 3.0).abs() < 1e-9);
+        assert!((h[4] - 1.0).abs() < 1e-9);
+        assert!(h[1].abs() < 1e-9);
+        assert!(harmonics_from_waveform(&samples, 2_000.0, f0).is_err());
+    }
+
+    #[test]
+    fn test_iec61000_3_12() {
+        // Rsce 100 -> Rsce 66 row: 3rd <= 24 %, THC <= 26 %
+        let h = spectrum(40.0, &[(3, 9.0), (5, 4.0), (7, 2.0)]);
+        let report = check_iec61000_3_12(&h, 100.0, false, None).unwrap();
+        assert!((report.harmonics[1].limit - 9.6).abs() < 1e-12);
+        assert!((report.thc_limit.unwrap() - 10.4).abs() < 1e-12);
+        assert!(report.passes);  // THC = sqrt(101) = 10.05 A
+
+        // Each order within its limit, but the total harmonic current is not
+        let h_total = spectrum(40.0, &[(3, 9.0), (5, 5.0), (7, 3.0)]);
+        let total = check_iec61000_3_12(&h_total, 100.0, false, None).unwrap();
+        assert!(total.harmonics.iter().all(|r| r.passes));
+        assert!(!total.passes);
+
+        // Balanced three-phase equipment has no individual 3rd harmonic limit
+        let balanced = check_iec61000_3_12(&h, 100.0, true, None).unwrap();
+        assert!(balanced.harmonics[1].limit.is_nan());
+        assert!(check_iec61000_3_12(&h, 20.0, false, None).is_err());
+    }
+}
@@ -0,0 +1,376 @@
+// wasm/src/harmonics.rs
+// Switching-frequency harmonic identification on measured spectra
+use serde::{Deserialize, Serialize};
+
+use crate::{Detector, EMCStandard, EmcError};
+
+// Minimum prominence (dB) for a local maximum to enter the peak table
+pub const DEFAULT_MIN_PROMINENCE: f64 = 6.0;
+
+// Peaks within this fraction of the fundamental from n * f0 are labelled as harmonic n
+const HARMONIC_TOLERANCE: f64 = 0.05;
+
+// One row of the peak table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpectrumPeak {
+    pub frequency: f64,
+    pub amplitude: f64,
+    pub limit: f64,
+    pub margin: f64,
+    pub passes: bool,
+    pub prominence: f64,
+    pub harmonic: Option<u32>,  // harmonic number of the switching frequency
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarmonicAnalysis {
+    pub fundamental: f64,
+    pub fundamental_estimate: Option<FundamentalEstimate>,  // set when the fundamental was not given
+    pub peaks: Vec<SpectrumPeak>,
+    pub envelope_slope: Option<f64>,  // dB/decade across the labelled harmonics
+    pub violating_harmonics: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundamentalCandidate {
+    pub frequency: f64,
+    pub confidence: f64,     // 0..1
+    pub matched_peaks: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundamentalEstimate {
+    pub fundamental: f64,
+    pub confidence: f64,
+    pub candidates: Vec<FundamentalCandidate>,  // best first
+}
+
+// Maximum number of candidates reported with an estimate
+const MAX_CANDIDATES: usize = 5;
+
+// Harmonic number of `frequency` if it lies within `tolerance` of a multiple of `fundamental`
+fn harmonic_number(frequency: f64, fundamental: f64, tolerance: f64) -> Option<u32> {
+    let harmonic = (frequency / fundamental).round();
+    if harmonic >= 1.0 && (frequency - harmonic * fundamental).abs() <= tolerance {
+        Some(harmonic as u32)
+    } else {
+        None
+    }
+}
+
+// Trace step around index i, used to widen the harmonic tolerance on coarse traces
+fn local_step(frequencies: &[f64], i: usize) -> f64 {
+    if i + 1 < frequencies.len() {
+        frequencies[i + 1] - frequencies[i]
+    } else if i > 0 {
+        frequencies[i] - frequencies[i - 1]
+    } else {
+        0.0
+    }
+}
+
+// Local maxima of the trace with their prominence in dB, as (index, prominence)
+pub fn find_peaks(amplitudes: &[f64], min_prominence: f64) -> Vec<(usize, f64)> {
+    let n = amplitudes.len();
+    let mut peaks = Vec::new();
+
+    for i in 0..n {
+        let amp = amplitudes[i];
+        let rises = i == 0 || amplitudes[i - 1] < amp;
+        let falls = i == n - 1 || amplitudes[i + 1] <= amp;
+        if !(rises && falls) || n < 3 {
+            continue;
+        }
+
+        // Lowest point on each side before the trace climbs above this peak;
+        // a side running straight into the trace edge does not constrain it
+        let left_min = amplitudes[..i].iter().rev().take_while(|&&a| a <= amp).fold(amp, |m, &a| m.min(a));
+        let right_min = amplitudes[i + 1..].iter().take_while(|&&a| a <= amp).fold(amp, |m, &a| m.min(a));
+        let reference = match (i == 0, i == n - 1) {
+            (true, _) => right_min,
+            (_, true) => left_min,
+            _ => left_min.max(right_min),
+        };
+
+        let prominence = amp - reference;
+        if prominence >= min_prominence {
+            peaks.push((i, prominence));
+        }
+    }
+
+    peaks
+}
+
+// Least-squares slope of amplitude against log10(frequency), in dB/decade
+pub fn envelope_slope(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let xs: Vec<f64> = points.iter().map(|(f, _)| f.log10()).collect();
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, a)| a).sum::<f64>() / n;
+
+    let sxx: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = xs.iter().zip(points.iter()).map(|(x, (_, a))| (x - mean_x) * (a - mean_y)).sum();
+
+    if sxx == 0.0 {
+        None
+    } else {
+        Some(sxy / sxx)
+    }
+}
+
+// Score a fundamental against the peak table as (confidence, refined fundamental, matched peaks).
+// Confidence is the prominence-weighted share of peaks explained by the comb times the
+// share of expected harmonics actually present, so sub-harmonics are penalised.
+fn score_fundamental(peaks: &[(f64, f64, f64)], fundamental: f64) -> (f64, f64, usize) {
+    let total_weight: f64 = peaks.iter().map(|p| p.1).sum();
+    let mut matched_weight = 0.0;
+    let mut harmonics: Vec<u32> = Vec::new();
+    let (mut sum_nf, mut sum_nn) = (0.0, 0.0);
+
+    for &(frequency, prominence, step) in peaks {
+        let tolerance = (HARMONIC_TOLERANCE * fundamental).max(step);
+        if let Some(n) = harmonic_number(frequency, fundamental, tolerance) {
+            matched_weight += prominence;
+            harmonics.push(n);
+            sum_nf += n as f64 * frequency;
+            sum_nn += (n as f64).powi(2);
+        }
+    }
+
+    if harmonics.is_empty() || total_weight <= 0.0 {
+        return (0.0, fundamental, 0);
+    }
+
+    harmonics.sort_unstable();
+    harmonics.dedup();
+    let expected = (harmonics[harmonics.len() - 1] - harmonics[0] + 1) as f64;
+    let coverage = harmonics.len() as f64 / expected;
+    let confidence = (matched_weight / total_weight) * coverage;
+
+    (confidence, sum_nf / sum_nn, harmonics.len())
+}
+
+// Estimate the switching frequency from the comb spacing of the trace's peaks
+pub fn estimate_fundamental(frequencies: &[f64], amplitudes: &[f64]) -> Option<FundamentalEstimate> {
+    let n = frequencies.len().min(amplitudes.len());
+    let peaks: Vec<(f64, f64, f64)> = find_peaks(&amplitudes[..n], DEFAULT_MIN_PROMINENCE)
+        .into_iter()
+        .map(|(i, prominence)| (frequencies[i], prominence, local_step(frequencies, i)))
+        .collect();
+
+    if peaks.len() < 2 {
+        return None;
+    }
+
+    // Candidates: spacing between neighbouring peaks and the lowest peaks themselves
+    let mut seeds: Vec<f64> = peaks.windows(2).map(|w| w[1].0 - w[0].0).collect();
+    seeds.extend(peaks.iter().take(3).map(|p| p.0));
+
+    let mut candidates: Vec<FundamentalCandidate> = Vec::new();
+    for seed in seeds.into_iter().filter(|&f| f > 0.0) {
+        // Score, then re-score at the least-squares fundamental of the matched comb
+        let (_, refined, _) = score_fundamental(&peaks, seed);
+        let (confidence, _, matched_peaks) = score_fundamental(&peaks, refined);
+        if matched_peaks < 2 {
+            continue;
+        }
+
+        let duplicate = candidates
+            .iter()
+            .any(|c| (c.frequency - refined).abs() <= HARMONIC_TOLERANCE * refined);
+        if !duplicate {
+            candidates.push(FundamentalCandidate { frequency: refined, confidence, matched_peaks });
+        }
+    }
+
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    candidates.truncate(MAX_CANDIDATES);
+
+    let best = candidates.first()?;
+    Some(FundamentalEstimate {
+        fundamental: best.frequency,
+        confidence: best.confidence,
+        candidates,
+    })
+}
+
+// Build the peak table and label peaks by harmonic number of `fundamental`,
+// estimating the fundamental from the trace when it is not known
+pub fn analyze_harmonics(
+    standard: &EMCStandard,
+    frequencies: &[f64],
+    amplitudes: &[f64],
+    detector: Detector,
+    fundamental: Option<f64>,
+) -> Result<HarmonicAnalysis, EmcError> {
+    let (fundamental, fundamental_estimate) = match fundamental {
+        Some(f0) => (f0, None),
+        None => {
+            let estimate = estimate_fundamental(frequencies, amplitudes)
+                .ok_or_else(|| EmcError::invalid("Could not estimate the switching frequency from the trace"))?;
+            (estimate.fundamental, Some(estimate))
+        }
+    };
+    if !fundamental.is_finite() || fundamental <= 0.0 {
+        return Err(EmcError::invalid("fundamental must be a positive frequency"));
+    }
+
+    let compliance = standard.check_compliance(frequencies, amplitudes, detector)?;
+    let n = compliance.len();
+
+    let mut peaks = Vec::new();
+    let mut candidates: Vec<(usize, u32)> = Vec::new();
+
+    for (i, prominence) in find_peaks(&amplitudes[..n], DEFAULT_MIN_PROMINENCE) {
+        let point = &compliance[i];
+
+        // Candidate harmonic number, tolerance widened to the local trace step
+        let tolerance = (HARMONIC_TOLERANCE * fundamental).max(local_step(frequencies, i));
+        if let Some(harmonic) = harmonic_number(point.frequency, fundamental, tolerance) {
+            candidates.push((peaks.len(), harmonic));
+        }
+
+        peaks.push(SpectrumPeak {
+            frequency: point.frequency,
+            amplitude: point.amplitude,
+            limit: point.limit,
+            margin: point.margin,
+            passes: point.passes,
+            prominence,
+            harmonic: None,
+        });
+    }
+
+    // Only the strongest peak near each harmonic carries its label
+    for &(p, harmonic) in &candidates {
+        let strongest = candidates
+            .iter()
+            .filter(|&&(_, h)| h == harmonic)
+            .all(|&(q, _)| peaks[q].amplitude <= peaks[p].amplitude);
+        if strongest && !peaks.iter().any(|peak| peak.harmonic == Some(harmonic)) {
+            peaks[p].harmonic = Some(harmonic);
+        }
+    }
+
+    let labelled: Vec<(f64, f64)> = peaks
+        .iter()
+        .filter(|peak| peak.harmonic.is_some())
+        .map(|peak| (peak.frequency, peak.amplitude))
+        .collect();
+
+    let violating_harmonics = peaks
+        .iter()
+        .filter(|peak| !peak.passes)
+        .filter_map(|peak| peak.harmonic)
+        .collect();
+
+    Ok(HarmonicAnalysis {
+        fundamental,
+        fundamental_estimate,
+        envelope_slope: envelope_slope(&labelled),
+        peaks,
+        violating_harmonics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 5 kHz grid with harmonics of `f0` falling at `slope` dB/decade above a 0 dBµV floor
+    fn harmonic_trace(f0: f64, first_level: f64, slope: f64) -> (Vec<f64>, Vec<f64>) {
+        let frequencies: Vec<f64> = (30..=6000).map(|i| i as f64 * 5_000.0).collect();
+        let amplitudes = frequencies
+            .iter()
+            .map(|&f| {
+                let n = (f / f0).round();
+                if n >= 1.0 && (f - n * f0).abs() < 1.0 {
+                    first_level + slope * n.log10()
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        (frequencies, amplitudes)
+    }
+
+    #[test]
+    fn test_find_peaks_prominence() {
+        let amplitudes = vec![10.0, 30.0, 12.0, 14.0, 11.0, 40.0, 10.0];
+        let peaks = find_peaks(&amplitudes, 6.0);
+
+        assert_eq!(peaks.iter().map(|p| p.0).collect::<Vec<_>>(), vec![1, 5]);
+        assert_eq!(peaks[0].1, 19.0);
+    }
+
+    #[test]
+    fn test_harmonic_labels_and_slope() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let (frequencies, amplitudes) = harmonic_trace(100_000.0, 110.0, -40.0);
+
+        let analysis = analyze_harmonics(&standard, &frequencies, &amplitudes, Detector::Avg, Some(100_000.0)).unwrap();
+
+        // Harmonics 2..=300 fall inside the 150 kHz - 30 MHz trace
+        let labelled: Vec<u32> = analysis.peaks.iter().filter_map(|p| p.harmonic).collect();
+        assert_eq!(labelled.first(), Some(&2));
+        assert_eq!(labelled.len(), 299);
+        assert!((analysis.envelope_slope.unwrap() + 40.0).abs() < 1e-6);
+
+        // Low harmonics exceed the Class B average limit, high ones do not
+        assert!(analysis.violating_harmonics.contains(&2));
+        assert!(!analysis.violating_harmonics.contains(&300));
+    }
+
+    #[test]
+    fn test_invalid_fundamental() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        assert!(analyze_harmonics(&standard, &[1e6], &[50.0], Detector::Avg, Some(0.0)).is_err());
+    }
+
+    #[test]
+    fn test_estimate_fundamental_from_comb() {
+        // 65 kHz converter: several harmonics missing, fundamental below the trace start
+        let (frequencies, mut amplitudes) = harmonic_trace(65_000.0, 110.0, -40.0);
+        for (f, a) in frequencies.iter().zip(amplitudes.iter_mut()) {
+            let n = (f / 65_000.0).round() as u32;
+            if n.is_multiple_of(7) {
+                *a = 0.0;
+            }
+        }
+
+        let estimate = estimate_fundamental(&frequencies, &amplitudes).unwrap();
+        assert!((estimate.fundamental - 65_000.0).abs() < 65.0, "f0 = {}", estimate.fundamental);
+        assert!(estimate.confidence > 0.8);
+        assert!(estimate.candidates.len() <= MAX_CANDIDATES);
+
+        // A sub-harmonic explains the same peaks but scores lower
+        let (half, _, _) = score_fundamental(
+            &find_peaks(&amplitudes, DEFAULT_MIN_PROMINENCE)
+                .into_iter()
+                .map(|(i, p)| (frequencies[i], p, 5_000.0))
+                .collect::<Vec<_>>(),
+            32_500.0,
+        );
+        assert!(half < estimate.confidence);
+    }
+
+    #[test]
+    fn test_analyze_with_estimated_fundamental() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let (frequencies, amplitudes) = harmonic_trace(250_000.0, 110.0, -40.0);
+
+        let analysis = analyze_harmonics(&standard, &frequencies, &amplitudes, Detector::Avg, None).unwrap();
+        assert!((analysis.fundamental - 250_000.0).abs() < 250.0);
+        assert!(analysis.fundamental_estimate.is_some());
+        assert_eq!(analysis.peaks.iter().filter(|p| p.harmonic.is_some()).count(), 120);
+    }
+
+    #[test]
+    fn test_estimate_needs_peaks() {
+        assert!(estimate_fundamental(&[1e6, 2e6, 3e6], &[40.0, 40.0, 40.0]).is_none());
+    }
+}
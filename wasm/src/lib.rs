@@ -1,22 +1,38 @@
 // wasm/src/lib.rs
-use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-// Include frequency helpers module
-mod frequency_helpers;
+pub use detectors::Detector;
+pub use error::EmcError;
 
-#[wasm_bindgen]
-extern "C" {
-    fn alert(s: &str);
-    
-    #[wasm_bindgen(js_namespace = console)]
-    fn log(s: &str);
-}
+// Include frequency helpers module
+pub mod frequency_helpers;
+pub mod batch;
+pub mod catalog;
+pub mod composite;
+pub mod harmonics;
+pub mod harmonic_current;
+pub mod limit_lines;
+pub mod limit_ops;
+pub mod lisn;
+pub mod measurement;
+pub mod noise_modes;
+pub mod current_probe;
+pub mod detectors;
+pub mod editions;
+pub mod error;
+pub mod filter_design;
+pub mod flicker;
+pub mod scan_plan;
+pub mod transducers;
+pub mod validation;
+pub mod time_domain;
 
-macro_rules! console_log {
-    ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
-}
+// wasm-bindgen layer, built with the default "wasm" feature
+#[cfg(feature = "wasm")]
+pub mod bindings;
+#[cfg(feature = "wasm")]
+pub use bindings::*;
 
 // Point structure for frequency-amplitude pairs
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,14 +47,174 @@ impl MaskPoint {
     }
 }
 
+// Unit of the limit values (and of the traces compared against them)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LimitUnit {
+    #[default]
+    #[serde(rename = "dBuV")]
+    DbMicroVolt,
+    #[serde(rename = "dBuA")]
+    DbMicroAmp,
+    #[serde(rename = "dBuV/m")]
+    DbMicroVoltPerMeter,
+}
+
+// Flat limits over a service band; a missing detector means "not applicable" in that band
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitBand {
+    pub name: String,
+    pub f_start: f64,  // Hz
+    pub f_stop: f64,   // Hz
+    #[serde(default)]
+    pub avg: Option<f64>,
+    #[serde(default)]
+    pub qp: Option<f64>,
+    #[serde(default)]
+    pub pk: Option<f64>,
+}
+
+// One detector's limit derived from another over a frequency range,
+// e.g. FCC Part 15 above 1 GHz: peak = average + 20 dB.
+// Without a reference the detector has no limit there (FCC Part 15 conducted peak).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectorRule {
+    pub detector: Detector,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<Detector>,  // detector the limit is derived from
+    #[serde(default)]
+    pub offset: f64,        // dB added to the reference limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub f_start: Option<f64>,  // Hz, unbounded when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub f_stop: Option<f64>,
+}
+
+impl DetectorRule {
+    pub fn applies_at(&self, frequency: f64) -> bool {
+        self.f_start.is_none_or(|f| frequency >= f) && self.f_stop.is_none_or(|f| frequency <= f)
+    }
+}
+
+// Selection dimensions beyond standard and class (port, installation category, ratings)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SelectionParameters {
+    #[serde(default)]
+    pub interface: Option<String>,   // e.g. "ac_mains", "dc_power"
+    #[serde(default)]
+    pub category: Option<String>,    // installation category, e.g. "C2"
+    #[serde(default)]
+    pub rated_current: Option<f64>,  // A
+    #[serde(default)]
+    pub power: Option<f64>,          // kVA
+}
+
+// Conditions under which a variant applies; numeric ranges are (min, max]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VariantSelector {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rated_current_min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rated_current_max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power_min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power_max: Option<f64>,
+}
+
+impl VariantSelector {
+    // Number of dimensions this selector constrains
+    pub fn specificity(&self) -> usize {
+        [self.interface.is_some(), self.category.is_some()].iter().filter(|&&c| c).count()
+            + [self.rated_current_min.is_some() || self.rated_current_max.is_some(),
+               self.power_min.is_some() || self.power_max.is_some()].iter().filter(|&&c| c).count()
+    }
+
+    // Every constrained dimension must be given and satisfied
+    pub fn matches(&self, parameters: &SelectionParameters) -> bool {
+        let text = |wanted: &Option<String>, given: &Option<String>| match (wanted, given) {
+            (None, _) => true,
+            (Some(w), Some(g)) => w.eq_ignore_ascii_case(g),
+            (Some(_), None) => false,
+        };
+        let range = |min: Option<f64>, max: Option<f64>, given: Option<f64>| match (min, max, given) {
+            (None, None, _) => true,
+            (_, _, None) => false,
+            (min, max, Some(x)) => min.is_none_or(|m| x > m) && max.is_none_or(|m| x <= m),
+        };
+
+        text(&self.interface, &parameters.interface)
+            && text(&self.category, &parameters.category)
+            && range(self.rated_current_min, self.rated_current_max, parameters.rated_current)
+            && range(self.power_min, self.power_max, parameters.power)
+    }
+}
+
+// Alternative limits within a class, picked by selection parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandardVariant {
+    #[serde(default)]
+    pub selector: VariantSelector,
+    #[serde(flatten)]
+    pub class: StandardClass,
+}
+
 // Standard class definition from JSON
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StandardClass {
     pub name: String,
     pub description: String,
+    #[serde(default)]
+    pub unit: LimitUnit,
+    #[serde(default)]
     pub avg_mask: Vec<(f64, f64)>,  // (frequency_hz, amplitude_dbuv)
     pub qp_mask: Option<Vec<(f64, f64)>>,
     pub pk_mask: Option<Vec<(f64, f64)>>,
+    // Band-table limits (e.g. CISPR 25); when present they replace the masks
+    #[serde(default)]
+    pub bands: Option<Vec<LimitBand>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detector_rules: Option<Vec<DetectorRule>>,
+    // Port / category / rating dependent limits; the most specific matching variant wins
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Vec<StandardVariant>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<catalog::StandardMetadata>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edition: Option<editions::StandardEdition>,
+}
+
+impl StandardClass {
+    pub(crate) fn has_limits(&self) -> bool {
+        !self.avg_mask.is_empty() || self.qp_mask.is_some() || self.pk_mask.is_some() || self.bands.is_some()
+    }
+
+    // Resolve the variant for the given selection parameters
+    pub fn select(&self, parameters: &SelectionParameters) -> Result<&StandardClass, EmcError> {
+        let Some(variants) = &self.variants else {
+            return Ok(self);
+        };
+
+        let best = variants
+            .iter()
+            .filter(|variant| variant.selector.matches(parameters))
+            .fold(None::<&StandardVariant>, |best, variant| match best {
+                Some(b) if b.selector.specificity() >= variant.selector.specificity() => Some(b),
+                _ => Some(variant),
+            });
+
+        match best {
+            Some(variant) => Ok(&variant.class),
+            None if self.has_limits() => Ok(self),
+            None => Err(EmcError::VariantNotFound {
+                class: self.name.clone(),
+                selectors: variants.iter().map(|v| serde_json::to_string(&v.selector).unwrap_or_default()).collect(),
+            }),
+        }
+    }
 }
 
 // Complete standard definition
@@ -59,12 +235,20 @@ pub struct EmcStandardsData {
 pub struct EMCStandard {
     pub name: String,
     pub description: String,
+    #[serde(default)]
+    pub unit: LimitUnit,
     pub f_avg_limit_mask: Vec<f64>,
     pub dbuv_avg_limit_mask: Vec<f64>,
     pub f_qp_limit_mask: Option<Vec<f64>>,
     pub dbuv_qp_limit_mask: Option<Vec<f64>>,
     pub f_pk_limit_mask: Option<Vec<f64>>,
     pub dbuv_pk_limit_mask: Option<Vec<f64>>,
+    #[serde(default)]
+    pub bands: Option<Vec<LimitBand>>,
+    #[serde(default)]
+    pub detector_rules: Option<Vec<DetectorRule>>,
+    #[serde(default)]
+    pub edition: Option<editions::StandardEdition>,
 }
 
 impl EMCStandard {
@@ -89,48 +273,186 @@ impl EMCStandard {
         Self {
             name: class.name.clone(),
             description: class.description.clone(),
+            unit: class.unit,
             f_avg_limit_mask: f_avg,
             dbuv_avg_limit_mask: dbuv_avg,
             f_qp_limit_mask: f_qp,
             dbuv_qp_limit_mask: dbuv_qp,
             f_pk_limit_mask: f_pk,
             dbuv_pk_limit_mask: dbuv_pk,
+            bands: class.bands.clone(),
+            detector_rules: class.detector_rules.clone(),
+            edition: class.edition.clone(),
+        }
+    }
+    
+    // Back to the standards JSON class format
+    pub fn to_standard_class(&self) -> StandardClass {
+        let zip = |f: &Option<Vec<f64>>, dbuv: &Option<Vec<f64>>| match (f, dbuv) {
+            (Some(f), Some(dbuv)) => Some(f.iter().cloned().zip(dbuv.iter().cloned()).collect()),
+            _ => None,
+        };
+        
+        StandardClass {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            unit: self.unit,
+            avg_mask: self.f_avg_limit_mask.iter().cloned().zip(self.dbuv_avg_limit_mask.iter().cloned()).collect(),
+            qp_mask: zip(&self.f_qp_limit_mask, &self.dbuv_qp_limit_mask),
+            pk_mask: zip(&self.f_pk_limit_mask, &self.dbuv_pk_limit_mask),
+            bands: self.bands.clone(),
+            detector_rules: self.detector_rules.clone(),
+            variants: None,
+            metadata: None,
+            edition: self.edition.clone(),
         }
     }
     
     // Load standards from embedded JSON
-    fn load_standards_data() -> Result<EmcStandardsData, String> {
+    pub(crate) fn load_standards_data() -> Result<EmcStandardsData, EmcError> {
         let json_data = include_str!("../emc_standards.json");
-        serde_json::from_str(json_data).map_err(|e| format!("Failed to parse standards JSON: {}", e))
+        Ok(serde_json::from_str(json_data)?)
+    }
+    
+    // Standard from its JSON form (as returned by get_emc_standard), with validated masks
+    pub fn from_json(json: &str) -> Result<EMCStandard, EmcError> {
+        let standard: EMCStandard = serde_json::from_str(json)?;
+        standard.validate()?;
+        Ok(standard)
+    }
+    
+    // Masks need matching lengths and finite, positive, ascending frequencies
+    pub fn validate(&self) -> Result<(), EmcError> {
+        let masks = [
+            ("avg", Some(&self.f_avg_limit_mask), Some(&self.dbuv_avg_limit_mask)),
+            ("qp", self.f_qp_limit_mask.as_ref(), self.dbuv_qp_limit_mask.as_ref()),
+            ("pk", self.f_pk_limit_mask.as_ref(), self.dbuv_pk_limit_mask.as_ref()),
+        ];
+        for (mask, f, dbuv) in masks {
+            let (f, dbuv) = match (f, dbuv) {
+                (Some(f), Some(dbuv)) => (f, dbuv),
+                (None, None) => continue,
+                _ => return Err(EmcError::InvalidMask { mask: mask.to_string(), reason: "frequencies and levels must both be given".to_string() }),
+            };
+            // "No limit" is a detector rule, not an empty QP/PK mask; an empty AVG mask means no mask
+            if f.is_empty() && mask != "avg" {
+                return Err(EmcError::InvalidMask { mask: mask.to_string(), reason: "mask has no points; omit it instead".to_string() });
+            }
+            if f.len() != dbuv.len() {
+                return Err(EmcError::LengthMismatch { field: format!("dbuv_{}_limit_mask", mask), expected: f.len(), found: dbuv.len() });
+            }
+            if f.iter().any(|&f| !f.is_finite() || f <= 0.0) {
+                return Err(EmcError::InvalidMask { mask: mask.to_string(), reason: "frequencies must be finite and positive".to_string() });
+            }
+            if f.windows(2).any(|w| w[1] < w[0]) {
+                return Err(EmcError::InvalidMask { mask: mask.to_string(), reason: "frequencies must be in ascending order".to_string() });
+            }
+        }
+        if self.f_avg_limit_mask.is_empty() && self.bands.is_none() && self.f_qp_limit_mask.is_none() {
+            return Err(EmcError::InvalidMask { mask: "avg".to_string(), reason: "a standard needs at least one limit".to_string() });
+        }
+        Ok(())
     }
     
     // Get a specific standard by name and class
-    pub fn get_standard(standard_name: &str, class_name: &str) -> Result<EMCStandard, String> {
-        let data = Self::load_standards_data()?;
+    pub fn get_standard(standard_name: &str, class_name: &str) -> Result<EMCStandard, EmcError> {
+        Self::get_standard_with(standard_name, class_name, &SelectionParameters::default())
+    }
+
+    // Get a standard, resolving port / category / rating dependent variants
+    pub fn get_standard_with(standard_name: &str, class_name: &str, parameters: &SelectionParameters) -> Result<EMCStandard, EmcError> {
+        let class = Self::get_class(standard_name, class_name)?;
+        let mut standard = Self::from_standard_class(class.select(parameters)?);
+        // Variants share the edition of their class
+        if standard.edition.is_none() {
+            standard.edition = class.edition.clone();
+        }
+        Ok(standard)
+    }
+
+    fn get_class(standard_name: &str, class_name: &str) -> Result<StandardClass, EmcError> {
+        let mut data = Self::load_standards_data()?;
         
-        let standard = data.standards.get(standard_name)
-            .ok_or_else(|| format!("Standard '{}' not found", standard_name))?;
+        let standard = data.standards.get_mut(standard_name)
+            .ok_or_else(|| EmcError::StandardNotFound { standard: standard_name.to_string() })?;
             
-        let class = standard.get(class_name)
-            .ok_or_else(|| format!("Class '{}' not found for standard '{}'", class_name, standard_name))?;
-            
-        Ok(Self::from_standard_class(class))
+        standard.remove(class_name).ok_or_else(|| {
+            let mut available: Vec<String> = standard.keys().cloned().collect();
+            available.sort();
+            EmcError::ClassNotFound { standard: standard_name.to_string(), class: class_name.to_string(), available }
+        })
+    }
+
+    // Selectors of the variants defined for a class (empty when the class has none)
+    pub fn list_variants(standard_name: &str, class_name: &str) -> Result<Vec<VariantSelector>, EmcError> {
+        let class = Self::get_class(standard_name, class_name)?;
+        Ok(class.variants.unwrap_or_default().into_iter().map(|v| v.selector).collect())
     }
     
     // List available standards
-    pub fn list_standards() -> Result<Vec<String>, String> {
+    pub fn list_standards() -> Result<Vec<String>, EmcError> {
         let data = Self::load_standards_data()?;
         Ok(data.standards.keys().cloned().collect())
     }
     
     // List available classes for a standard
-    pub fn list_classes(standard_name: &str) -> Result<Vec<String>, String> {
+    pub fn list_classes(standard_name: &str) -> Result<Vec<String>, EmcError> {
         let data = Self::load_standards_data()?;
         let standard = data.standards.get(standard_name)
-            .ok_or_else(|| format!("Standard '{}' not found", standard_name))?;
+            .ok_or_else(|| EmcError::StandardNotFound { standard: standard_name.to_string() })?;
         Ok(standard.keys().cloned().collect())
     }
     
+    // Lowest and highest frequency covered by any of the masks
+    pub fn frequency_span(&self) -> (f64, f64) {
+        let masks = [
+            Some(&self.f_avg_limit_mask),
+            self.f_qp_limit_mask.as_ref(),
+            self.f_pk_limit_mask.as_ref(),
+        ];
+        
+        let span = masks.iter().flatten().filter(|mask| !mask.is_empty()).fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(lo, hi), mask| (lo.min(mask[0]), hi.max(mask[mask.len() - 1])),
+        );
+        
+        self.bands.iter().flatten().fold(span, |(lo, hi), band| (lo.min(band.f_start), hi.max(band.f_stop)))
+    }
+    
+    // Mask points and band edges, where the limits can step or change slope
+    pub fn breakpoints(&self) -> Vec<f64> {
+        let masks = [Some(&self.f_avg_limit_mask), self.f_qp_limit_mask.as_ref(), self.f_pk_limit_mask.as_ref()];
+        let mut points: Vec<f64> = masks.iter().flatten().flat_map(|mask| mask.iter().cloned()).collect();
+        for band in self.bands.iter().flatten() {
+            points.push(band.f_start);
+            points.push(band.f_stop);
+        }
+        points
+    }
+    
+    // Band-table limits at a frequency; NaN marks detectors not applicable there.
+    // Where service bands overlap the most stringent limit applies.
+    fn band_limits(bands: &[LimitBand], frequency: f64) -> EMCLimitResult {
+        let tightest = |detector: fn(&LimitBand) -> Option<f64>| {
+            bands.iter()
+                .filter(|band| frequency >= band.f_start && frequency <= band.f_stop)
+                .filter_map(detector)
+                .fold(f64::NAN, f64::min)
+        };
+        let avg_limit = tightest(|band| band.avg);
+        let qp_limit = tightest(|band| band.qp);
+        let pk_limit = tightest(|band| band.pk);
+        
+        EMCLimitResult {
+            avg_limit,
+            qp_limit,
+            pk_limit,
+            dbuv_avg_limit: avg_limit,
+            dbuv_qp_limit: qp_limit,
+            dbuv_pk_limit: pk_limit,
+        }
+    }
+    
     // Improved logarithmic interpolation 
     fn interpolate_log(frequencies: &[f64], amplitudes: &[f64], target_freq: f64) -> f64 {
         if frequencies.is_empty() || amplitudes.is_empty() || frequencies.len() != amplitudes.len() {
@@ -162,6 +484,20 @@ impl EMCStandard {
     
     // Interpolate limit at a specific frequency
     pub fn interp_log(&self, frequency: f64) -> EMCLimitResult {
+        let mut limits = match &self.bands {
+            Some(bands) => Self::band_limits(bands, frequency),
+            None => self.mask_limits(frequency),
+        };
+        
+        // Detector relationships take precedence over masks and the generic fallbacks
+        for rule in self.detector_rules.iter().flatten().filter(|rule| rule.applies_at(frequency)) {
+            let value = rule.reference.map_or(f64::NAN, |reference| limits.for_detector(reference) + rule.offset);
+            limits.set(rule.detector, value);
+        }
+        limits
+    }
+    
+    fn mask_limits(&self, frequency: f64) -> EMCLimitResult {
         let avg_limit = Self::interpolate_log(&self.f_avg_limit_mask, &self.dbuv_avg_limit_mask, frequency);
         
         let qp_limit = if let (Some(f_qp), Some(dbuv_qp)) = (&self.f_qp_limit_mask, &self.dbuv_qp_limit_mask) {
@@ -186,6 +522,21 @@ impl EMCStandard {
         }
     }
     
+    // Point-by-point comparison against the limit for the given detector.
+    // The trace must be non-empty, finite, with positive ascending frequencies (see `validation`).
+    pub fn check_compliance(&self, frequencies: &[f64], amplitudes: &[f64], detector: Detector) -> Result<Vec<ComplianceResult>, EmcError> {
+        validation::validate_trace(frequencies, amplitudes, &validation::ValidationOptions::default())?;
+        let mut results = Vec::new();
+        
+        for (&freq, &amp) in frequencies.iter().zip(amplitudes.iter()) {
+            let limit_value = self.interp_log(freq).for_detector(detector);
+            
+            results.push(ComplianceResult::new(freq, amp, limit_value));
+        }
+        
+        Ok(results)
+    }
+    
     // Manual percentile calculation for WASM compatibility
     fn calculate_percentile(sorted_data: &[f64], percentile: f64) -> f64 {
         if sorted_data.is_empty() {
@@ -219,8 +570,19 @@ impl EMCStandard {
         variance.sqrt()
     }
     
-    // Advanced EMC analysis using statistical methods
-    pub fn analyze_measurement_statistics(&self, frequencies: &[f64], amplitudes: &[f64]) -> EMCStatistics {
+    // Advanced EMC analysis using statistical methods; violation fields against the AVG limit
+    pub fn analyze_measurement_statistics(&self, frequencies: &[f64], amplitudes: &[f64]) -> Result<EMCStatistics, EmcError> {
+        self.analyze_detector_statistics(frequencies, amplitudes, Detector::Avg)
+    }
+    
+    // Statistics of a trace read with `detector`; `detectors` summarises it against all three limits.
+    // The trace must be non-empty and finite.
+    pub fn analyze_detector_statistics(&self, frequencies: &[f64], amplitudes: &[f64], detector: Detector) -> Result<EMCStatistics, EmcError> {
+        // Order does not matter for the statistics, so unsorted traces are accepted
+        let options = validation::ValidationOptions { sort: true, drop_invalid: false };
+        let trace = validation::validate_trace(frequencies, amplitudes, &options)?;
+        let (frequencies, amplitudes) = (&trace.frequencies[..], &trace.amplitudes[..]);
+        
         // Calculate basic statistics
         let mean_amplitude = amplitudes.iter().sum::<f64>() / amplitudes.len() as f64;
         let std_amplitude = Self::calculate_std(amplitudes);
@@ -229,51 +591,62 @@ impl EMCStandard {
         
         // Calculate percentiles
         let mut sorted_amps = amplitudes.to_vec();
-        sorted_amps.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        sorted_amps.sort_by(|a, b| a.total_cmp(b));
         let p95 = Self::calculate_percentile(&sorted_amps, 95.0);
         let p99 = Self::calculate_percentile(&sorted_amps, 99.0);
         
-        // Find worst-case violations
-        let mut violations = Vec::new();
-        let mut max_violation = 0.0;
-        let mut max_violation_freq = 0.0;
-        
-        for (&freq, &amp) in frequencies.iter().zip(amplitudes.iter()) {
-            let limit = self.interp_log(freq);
-            let margin = limit.dbuv_avg_limit - amp;
-            
-            if margin < 0.0 {
-                violations.push(ComplianceResult {
-                    frequency: freq,
-                    amplitude: amp,
-                    limit: limit.dbuv_avg_limit,
-                    passes: false,
-                    margin,
-                });
-                
-                if margin.abs() > max_violation {
-                    max_violation = margin.abs();
-                    max_violation_freq = freq;
-                }
-            }
-        }
+        // Violations and margins per detector limit
+        let detectors = Detector::ALL
+            .into_iter()
+            .map(|d| {
+                let summary = ComplianceSummary::from_results(&self.check_compliance(frequencies, amplitudes, d)?);
+                let compliance_rate = if summary.points_with_limit == 0 {
+                    f64::NAN
+                } else {
+                    ((summary.points_with_limit - summary.violations) as f64 / summary.points_with_limit as f64) * 100.0
+                };
+                Ok(DetectorStatistics { detector: d, summary, compliance_rate })
+            })
+            .collect::<Result<Vec<_>, EmcError>>()?;
+        let selected = &detectors[detector.index()];
+        let (max_violation, max_violation_freq) = if selected.summary.violations > 0 {
+            (-selected.summary.worst_margin, selected.summary.worst_frequency)
+        } else {
+            (0.0, 0.0)
+        };
         
-        EMCStatistics {
+        Ok(EMCStatistics {
             mean_amplitude,
             std_amplitude,
             max_amplitude,
             min_amplitude,
             percentile_95: p95,
             percentile_99: p99,
-            violation_count: violations.len(),
+            violation_count: selected.summary.violations,
             max_violation,
             max_violation_frequency: max_violation_freq,
-            compliance_rate: ((frequencies.len() - violations.len()) as f64 / frequencies.len() as f64) * 100.0,
-        }
+            compliance_rate: selected.compliance_rate,
+            detector,
+            detectors,
+        })
+    }
+    
+    // Whether any of the frequencies lies within the standard's coverage
+    pub fn covers_any(&self, frequencies: &[f64]) -> bool {
+        let (lo, hi) = self.frequency_span();
+        frequencies.iter().any(|&f| f >= lo && f <= hi)
+    }
+    
+    // Mask with a fixed point density over [f_min, f_max]
+    pub fn generate_mask(&self, f_min: f64, f_max: f64, points_per_decade: usize) -> EMCMask {
+        let total_points = ((f_max.log10() - f_min.log10()) * points_per_decade as f64).ceil() as usize;
+        self.generate_adaptive_mask(f_min, f_max, total_points)
     }
     
     // Improved mask generation with logarithmic spacing
     pub fn generate_adaptive_mask(&self, f_min: f64, f_max: f64, target_points: usize) -> EMCMask {
+        // Always at least f_min and f_max, however narrow the range
+        let target_points = target_points.max(2);
         let log_min = f_min.log10();
         let log_max = f_max.log10();
         
@@ -282,25 +655,41 @@ impl EMCStandard {
         let mut pk_points = Vec::new();
         
         // Create logarithmically spaced frequency points
-        for i in 0..target_points {
-            let log_f = log_min + (i as f64 / (target_points - 1) as f64) * (log_max - log_min);
-            let freq = 10.0_f64.powf(log_f);
-            
+        let mut frequencies: Vec<f64> = (0..target_points)
+            .map(|i| 10.0_f64.powf(log_min + (i as f64 / (target_points - 1) as f64) * (log_max - log_min)))
+            .collect();
+        frequencies[0] = f_min;
+        frequencies[target_points - 1] = f_max;
+        
+        // Band edges keep narrow bands visible between the log-spaced points
+        if let Some(bands) = &self.bands {
+            frequencies.extend(
+                bands.iter()
+                    .flat_map(|band| [band.f_start, band.f_stop])
+                    .filter(|&f| f >= f_min && f <= f_max),
+            );
+            frequencies.sort_by(|a, b| a.total_cmp(b));
+            frequencies.dedup();
+        }
+        
+        for freq in frequencies {
             let limit = self.interp_log(freq);
             
-            avg_points.push(MaskPoint { 
-                frequency: freq, 
-                amplitude: limit.dbuv_avg_limit 
-            });
+            if !limit.dbuv_avg_limit.is_nan() {
+                avg_points.push(MaskPoint { 
+                    frequency: freq, 
+                    amplitude: limit.dbuv_avg_limit 
+                });
+            }
             
-            if limit.dbuv_qp_limit > 0.0 {
+            if !limit.dbuv_qp_limit.is_nan() {
                 qp_points.push(MaskPoint { 
                     frequency: freq, 
                     amplitude: limit.dbuv_qp_limit 
                 });
             }
             
-            if limit.dbuv_pk_limit > 0.0 {
+            if !limit.dbuv_pk_limit.is_nan() {
                 pk_points.push(MaskPoint { 
                     frequency: freq, 
                     amplitude: limit.dbuv_pk_limit 
@@ -326,13 +715,79 @@ pub struct EMCLimitResult {
     pub dbuv_pk_limit: f64,
 }
 
+impl EMCLimitResult {
+    pub fn for_detector(&self, detector: Detector) -> f64 {
+        match detector {
+            Detector::Avg => self.dbuv_avg_limit,
+            Detector::Qp => self.dbuv_qp_limit,
+            Detector::Pk => self.dbuv_pk_limit,
+        }
+    }
+    
+    fn set(&mut self, detector: Detector, value: f64) {
+        match detector {
+            Detector::Avg => (self.avg_limit, self.dbuv_avg_limit) = (value, value),
+            Detector::Qp => (self.qp_limit, self.dbuv_qp_limit) = (value, value),
+            Detector::Pk => (self.pk_limit, self.dbuv_pk_limit) = (value, value),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComplianceResult {
-    pub frequency: f64,
+    pub frequency: f64,  // Hz, NaN for scalar quantities
     pub amplitude: f64,
     pub limit: f64,
     pub passes: bool,
     pub margin: f64,
+    // Name of a scalar quantity checked against its limit (flicker indices, relative voltage changes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<String>,
+}
+
+impl ComplianceResult {
+    // Values without an applicable limit pass with a NaN limit and margin
+    pub fn new(frequency: f64, amplitude: f64, limit: f64) -> ComplianceResult {
+        ComplianceResult {
+            frequency,
+            amplitude,
+            limit,
+            passes: limit.is_nan() || amplitude <= limit,
+            margin: limit - amplitude,
+            quantity: None,
+        }
+    }
+
+    pub fn for_quantity(quantity: &str, value: f64, limit: f64) -> ComplianceResult {
+        ComplianceResult { quantity: Some(quantity.to_string()), ..ComplianceResult::new(f64::NAN, value, limit) }
+    }
+}
+
+// Overall verdict of a point-by-point comparison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceSummary {
+    pub points: usize,
+    pub points_with_limit: usize,
+    pub violations: usize,
+    pub worst_margin: f64,     // dB, NaN when no point has an applicable limit
+    pub worst_frequency: f64,  // Hz
+    pub passes: bool,
+}
+
+impl ComplianceSummary {
+    pub fn from_results(results: &[ComplianceResult]) -> ComplianceSummary {
+        let limited: Vec<&ComplianceResult> = results.iter().filter(|r| !r.limit.is_nan()).collect();
+        let worst = limited.iter().min_by(|a, b| a.margin.total_cmp(&b.margin));
+
+        ComplianceSummary {
+            points: results.len(),
+            points_with_limit: limited.len(),
+            violations: results.iter().filter(|r| !r.passes).count(),
+            worst_margin: worst.map_or(f64::NAN, |r| r.margin),
+            worst_frequency: worst.map_or(f64::NAN, |r| r.frequency),
+            passes: results.iter().all(|r| r.passes),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -354,138 +809,24 @@ pub struct EMCStatistics {
     pub max_violation: f64,
     pub max_violation_frequency: f64,
     pub compliance_rate: f64,
+    #[serde(default)]
+    pub detector: Detector,  // limit the violation fields above refer to
+    #[serde(default)]
+    pub detectors: Vec<DetectorStatistics>,  // avg, qp, pk
 }
 
-// Legacy interpolation function for compatibility
-fn log_interp(x_points: &[f64], y_points: &[f64], x: f64) -> f64 {
-    EMCStandard::interpolate_log(x_points, y_points, x)
-}
-
-// WASM bindings
-#[wasm_bindgen(start)]
-pub fn init() {
-    console_error_panic_hook::set_once();
-}
-
-#[wasm_bindgen]
-pub fn get_emc_standard(standard_name: &str, emc_class: &str, _interface: Option<String>) -> Result<JsValue, JsValue> {
-    console_log!("Getting EMC standard: {} {}", standard_name, emc_class);
-    
-    match EMCStandard::get_standard(standard_name, emc_class) {
-        Ok(standard) => {
-            console_log!("Standard loaded successfully: {}", standard.name);
-            serde_wasm_bindgen::to_value(&standard).map_err(|e| JsValue::from_str(&e.to_string()))
-        },
-        Err(e) => {
-            console_log!("Error loading standard: {}", e);
-            Err(JsValue::from_str(&e))
-        }
-    }
-}
-
-#[wasm_bindgen]
-pub fn list_available_standards() -> Result<JsValue, JsValue> {
-    match EMCStandard::list_standards() {
-        Ok(standards) => serde_wasm_bindgen::to_value(&standards).map_err(|e| JsValue::from_str(&e.to_string())),
-        Err(e) => Err(JsValue::from_str(&e))
-    }
-}
-
-#[wasm_bindgen]
-pub fn list_standard_classes(standard_name: &str) -> Result<JsValue, JsValue> {
-    match EMCStandard::list_classes(standard_name) {
-        Ok(classes) => serde_wasm_bindgen::to_value(&classes).map_err(|e| JsValue::from_str(&e.to_string())),
-        Err(e) => Err(JsValue::from_str(&e))
-    }
-}
-
-#[wasm_bindgen]
-pub fn calculate_emc_limit(standard_json: &str, frequency: f64) -> Result<JsValue, JsValue> {
-    let standard: EMCStandard = serde_json::from_str(standard_json)
-        .map_err(|e| JsValue::from_str(&e.to_string()))?;
-
-    let result = standard.interp_log(frequency);
-    serde_wasm_bindgen::to_value(&result).map_err(|e| JsValue::from_str(&e.to_string()))
-}
-
-#[wasm_bindgen]
-pub fn check_emc_compliance(
-    standard_json: &str,
-    frequencies: &[f64],
-    amplitudes: &[f64],
-    measurement_type: &str
-) -> Result<JsValue, JsValue> {
-    let standard: EMCStandard = serde_json::from_str(standard_json)
-        .map_err(|e| JsValue::from_str(&e.to_string()))?;
-
-    let mut results = Vec::new();
-    
-    for (&freq, &amp) in frequencies.iter().zip(amplitudes.iter()) {
-        let limit = standard.interp_log(freq);
-        
-        let limit_value = match measurement_type.to_lowercase().as_str() {
-            "avg" => limit.dbuv_avg_limit,
-            "qp" => limit.dbuv_qp_limit,
-            "pk" => limit.dbuv_pk_limit,
-            _ => return Err(JsValue::from_str("measurement_type must be 'avg', 'qp', or 'pk'")),
-        };
-        
-        results.push(ComplianceResult {
-            frequency: freq,
-            amplitude: amp,
-            limit: limit_value,
-            passes: amp <= limit_value,
-            margin: limit_value - amp,
-        });
-    }
-
-    serde_wasm_bindgen::to_value(&results).map_err(|e| JsValue::from_str(&e.to_string()))
-}
-
-#[wasm_bindgen]
-pub fn generate_emc_mask(
-    standard_json: &str,
-    f_min: f64,
-    f_max: f64,
-    points_per_decade: usize
-) -> Result<JsValue, JsValue> {
-    let standard: EMCStandard = serde_json::from_str(standard_json)
-        .map_err(|e| JsValue::from_str(&e.to_string()))?;
-
-    let log_min = f_min.log10();
-    let log_max = f_max.log10();
-    let total_points = ((log_max - log_min) * points_per_decade as f64).ceil() as usize;
-    
-    let mask = standard.generate_adaptive_mask(f_min, f_max, total_points);
-    serde_wasm_bindgen::to_value(&mask).map_err(|e| JsValue::from_str(&e.to_string()))
-}
-
-// Enhanced WASM bindings for statistical analysis
-#[wasm_bindgen]
-pub fn analyze_emc_statistics(
-    standard_json: &str,
-    frequencies: &[f64],
-    amplitudes: &[f64]
-) -> Result<JsValue, JsValue> {
-    let standard: EMCStandard = serde_json::from_str(standard_json)
-        .map_err(|e| JsValue::from_str(&e.to_string()))?;
-
-    let stats = standard.analyze_measurement_statistics(frequencies, amplitudes);
-    serde_wasm_bindgen::to_value(&stats).map_err(|e| JsValue::from_str(&e.to_string()))
+// One detector limit applied to the whole trace; points without a limit are left out of the rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectorStatistics {
+    pub detector: Detector,
+    #[serde(flatten)]
+    pub summary: ComplianceSummary,
+    pub compliance_rate: f64,  // % of the points with a limit, NaN when none has one
 }
 
-#[wasm_bindgen]
-pub fn generate_adaptive_emc_mask(
-    standard_json: &str,
-    f_min: f64,
-    f_max: f64,
-    target_points: usize
-) -> Result<JsValue, JsValue> {
-    let standard: EMCStandard = serde_json::from_str(standard_json)
-        .map_err(|e| JsValue::from_str(&e.to_string()))?;
-
-    let mask = standard.generate_adaptive_mask(f_min, f_max, target_points);
-    serde_wasm_bindgen::to_value(&mask).map_err(|e| JsValue::from_str(&e.to_string()))
+// Legacy interpolation function for compatibility
+pub(crate) fn log_interp(x_points: &[f64], y_points: &[f64], x: f64) -> f64 {
+    EMCStandard::interpolate_log(x_points, y_points, x)
 }
 
 #[cfg(test)]
@@ -545,6 +886,9 @@ mod tests {
         assert!(result.dbuv_avg_limit > 0.0);
         assert!(result.dbuv_qp_limit > result.dbuv_avg_limit);
         assert!(result.dbuv_pk_limit > result.dbuv_qp_limit);
+        
+        // Peak stays flat from 30 MHz and steps at 100 MHz, like the QP/AVG masks
+        assert_eq!(standard.interp_log(50e6).dbuv_pk_limit, 56.0);
     }
     
     #[test]
@@ -569,7 +913,7 @@ mod tests {
         let frequencies = vec![500_000.0, 1_000_000.0, 5_000_000.0, 10_000_000.0];
         let amplitudes = vec![70.0, 65.0, 55.0, 50.0]; // Some values above/below limits
         
-        let stats = standard.analyze_measurement_statistics(&frequencies, &amplitudes);
+        let stats = standard.analyze_measurement_statistics(&frequencies, &amplitudes).unwrap();
         
         assert_eq!(stats.mean_amplitude, 60.0);
         assert!(stats.std_amplitude > 0.0);
@@ -635,7 +979,7 @@ mod tests {
         ];
         let amplitudes = vec![85.0, 78.0, 72.0, 68.0, 65.0, 62.0, 58.0, 55.0];
         
-        let stats = standard.analyze_measurement_statistics(&frequencies, &amplitudes);
+        let stats = standard.analyze_measurement_statistics(&frequencies, &amplitudes).unwrap();
         
         // Verify reasonable statistical values
         assert!(stats.mean_amplitude > 50.0 && stats.mean_amplitude < 90.0);
@@ -680,7 +1024,7 @@ mod tests {
         // Test statistics
         let frequencies = vec![500_000.0, 1_000_000.0, 5_000_000.0];
         let amplitudes = vec![75.0, 70.0, 45.0]; // Mix of pass/fail
-        let stats = standard.analyze_measurement_statistics(&frequencies, &amplitudes);
+        let stats = standard.analyze_measurement_statistics(&frequencies, &amplitudes).unwrap();
         
         println!("Statistics:");
         println!("  Mean: {:.1} dBμV", stats.mean_amplitude);
@@ -694,4 +1038,239 @@ mod tests {
         assert!(stats.mean_amplitude > 50.0);
         assert!(stats.violation_count <= frequencies.len());
     }
+    
+    #[test]
+    fn test_band_table_limits() {
+        let standard = EMCStandard::get_standard("CISPR25_Conducted_Voltage", "Class5").unwrap();
+        assert!(standard.bands.is_some());
+        
+        // MW band
+        let mw = standard.interp_log(1_000_000.0);
+        assert_eq!((mw.dbuv_pk_limit, mw.dbuv_qp_limit, mw.dbuv_avg_limit), (54.0, 41.0, 34.0));
+        
+        // Between bands no limit applies
+        let gap = standard.interp_log(10_000_000.0);
+        assert!(gap.dbuv_pk_limit.is_nan() && gap.dbuv_avg_limit.is_nan());
+        
+        // VHF and TV Band I overlap: tightest limit per detector
+        let overlap = standard.interp_log(45_000_000.0);
+        assert_eq!(overlap.dbuv_pk_limit, 34.0);
+        assert_eq!(overlap.dbuv_qp_limit, 31.0);
+    }
+    
+    #[test]
+    fn test_band_table_compliance_and_mask() {
+        let standard = EMCStandard::get_standard("CISPR25_Conducted_Voltage", "Class3").unwrap();
+        let results = standard.check_compliance(&[1e6, 10e6], &[80.0, 80.0], Detector::Pk).unwrap();
+        
+        assert!(!results[0].passes);
+        assert!(results[1].passes && results[1].limit.is_nan());
+        
+        // The narrow SW band survives a coarse mask
+        let mask = standard.generate_adaptive_mask(150e3, 108e6, 10);
+        assert!(mask.pk.iter().any(|p| p.frequency >= 5.9e6 && p.frequency <= 6.2e6));
+        assert!(mask.avg.iter().all(|p| !p.amplitude.is_nan()));
+        assert_eq!(standard.frequency_span(), (150e3, 108e6));
+    }
+    
+    #[test]
+    fn test_cispr25_units_and_classes() {
+        let classes = EMCStandard::list_classes("CISPR25_Radiated_ALSE").unwrap();
+        assert_eq!(classes.len(), 5);
+        
+        let current = EMCStandard::get_standard("CISPR25_Conducted_Current", "Class5").unwrap();
+        assert_eq!(current.unit, LimitUnit::DbMicroAmp);
+        // Negative dBµA limits are kept in the mask
+        let mask = current.generate_adaptive_mask(76e6, 108e6, 5);
+        assert!(mask.avg.iter().any(|p| p.amplitude < 0.0));
+        
+        let radiated = EMCStandard::get_standard("CISPR25_Radiated_ALSE", "Class1").unwrap();
+        assert_eq!(radiated.unit, LimitUnit::DbMicroVoltPerMeter);
+        let gps = radiated.interp_log(1_575.42e6);
+        assert!(gps.dbuv_pk_limit.is_nan());
+        assert_eq!(gps.dbuv_avg_limit, 34.0);
+    }
+    
+    #[test]
+    fn test_category_and_current_selection() {
+        let mut parameters = SelectionParameters {
+            interface: Some("AC_Mains".to_string()),
+            category: Some("C3".to_string()),
+            rated_current: Some(150.0),
+            ..Default::default()
+        };
+        let high = EMCStandard::get_standard_with("IEC61800_3", "Conducted", &parameters).unwrap();
+        assert_eq!(high.name, "IEC 61800-3 C3 Conducted, I > 100 A");
+        assert_eq!(high.interp_log(1e6).dbuv_qp_limit, 125.0);
+        
+        parameters.rated_current = Some(100.0);
+        let low = EMCStandard::get_standard_with("IEC61800_3", "Conducted", &parameters).unwrap();
+        assert_eq!(low.name, "IEC 61800-3 C3 Conducted, I <= 100 A");
+        
+        // C3 needs the rated current; a class with only variants has no default
+        parameters.rated_current = None;
+        assert!(EMCStandard::get_standard_with("IEC61800_3", "Conducted", &parameters).is_err());
+        assert!(EMCStandard::get_standard("IEC61800_3", "Conducted").is_err());
+        assert_eq!(EMCStandard::list_variants("IEC61800_3", "Conducted").unwrap().len(), 4);
+    }
+    
+    #[test]
+    fn test_interface_and_power_selection() {
+        let parameters = SelectionParameters {
+            interface: Some("dc_power".to_string()),
+            power: Some(50.0),
+            ..Default::default()
+        };
+        let standard = EMCStandard::get_standard_with("CISPR11_Group1", "ClassA", &parameters).unwrap();
+        assert_eq!(standard.name, "CISPR 11 Group 1 Class A DC, 20 to 75 kVA");
+        
+        let selector = VariantSelector { interface: Some("ac_mains".to_string()), ..Default::default() };
+        assert_eq!(selector.specificity(), 1);
+        assert!(!selector.matches(&SelectionParameters::default()));
+        
+        // Classes without variants ignore the selection parameters
+        assert!(EMCStandard::get_standard_with("CISPR22", "ClassB", &parameters).is_ok());
+    }
+    
+    #[test]
+    fn test_fcc_part15_detector_rules() {
+        let radiated = EMCStandard::get_standard("FCC_Part15_Radiated", "ClassB").unwrap();
+        
+        // QP only below 1 GHz
+        let vhf = radiated.interp_log(100e6);
+        assert_eq!(vhf.dbuv_qp_limit, 43.5);
+        assert!(vhf.dbuv_avg_limit.is_nan() && vhf.dbuv_pk_limit.is_nan());
+        
+        // Above 1 GHz: average limit, peak = average + 20 dB
+        let microwave = radiated.interp_log(2.4e9);
+        assert_eq!(microwave.dbuv_avg_limit, 54.0);
+        assert_eq!(microwave.dbuv_pk_limit, 74.0);
+        assert!(microwave.dbuv_qp_limit.is_nan());
+        
+        let mask = radiated.generate_adaptive_mask(30e6, 6e9, 20);
+        assert!(mask.pk.iter().all(|p| p.frequency >= 1e9 && p.amplitude == 74.0));
+        
+        // Conducted: QP and AVG, no peak limit instead of the QP + 10 dB fallback
+        let conducted = EMCStandard::get_standard("FCC_Part15_Conducted", "ClassA").unwrap();
+        let limits = conducted.interp_log(1e6);
+        assert_eq!((limits.dbuv_qp_limit, limits.dbuv_avg_limit), (73.0, 60.0));
+        assert!(limits.dbuv_pk_limit.is_nan());
+        let results = conducted.check_compliance(&[1e6], &[90.0], Detector::Pk).unwrap();
+        assert!(results[0].passes);
+        
+        // An empty mask is no longer a "no limit" marker
+        let mut empty = conducted.clone();
+        empty.f_pk_limit_mask = Some(Vec::new());
+        empty.dbuv_pk_limit_mask = Some(Vec::new());
+        assert_eq!(empty.validate().unwrap_err().code(), "INVALID_MASK");
+    }
+    
+    #[test]
+    fn test_generate_mask_points_per_decade() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        // 150 kHz - 30 MHz is log10(200) = 2.3 decades
+        let mask = standard.generate_mask(150_000.0, 30_000_000.0, 10);
+        assert_eq!(mask.avg.len(), 24);
+        assert!((mask.avg[0].frequency - 150_000.0).abs() < 1e-6);
+        
+        // Less than a point's worth of range still gives both end points
+        let narrow = standard.generate_mask(1e6, 1.5e6, 1);
+        assert_eq!(narrow.avg.len(), 2);
+        assert!(narrow.avg.iter().chain(&narrow.qp).chain(&narrow.pk).all(|p| p.frequency.is_finite() && p.amplitude.is_finite()));
+        assert_eq!(narrow.qp[1].frequency, 1.5e6);
+        assert_eq!(standard.generate_adaptive_mask(1e6, 2e6, 0).avg.len(), 2);
+    }
+    
+    #[test]
+    fn test_bundled_standards_are_valid() {
+        // Every class and every variant loads and validates; variant-only classes have no base limits
+        for name in EMCStandard::list_standards().unwrap() {
+            for class in EMCStandard::list_classes(&name).unwrap() {
+                if EMCStandard::get_class(&name, &class).unwrap().has_limits() {
+                    assert_eq!(EMCStandard::get_standard(&name, &class).unwrap().validate(), Ok(()), "{} {}", name, class);
+                }
+                for selector in EMCStandard::list_variants(&name, &class).unwrap() {
+                    let parameters = SelectionParameters {
+                        interface: selector.interface.clone(),
+                        category: selector.category.clone(),
+                        rated_current: selector.rated_current_max.or(selector.rated_current_min.map(|min| min + 1.0)),
+                        power: selector.power_max.or(selector.power_min.map(|min| min + 1.0)),
+                    };
+                    let standard = EMCStandard::get_standard_with(&name, &class, &parameters).unwrap();
+                    assert_eq!(standard.validate(), Ok(()), "{} {} {:?}", name, class, selector);
+                }
+            }
+        }
+    }
+    
+    #[test]
+    fn test_standard_validation_and_typed_errors() {
+        assert_eq!(EMCStandard::get_standard("CISPR99", "ClassB").unwrap_err().code(), "STANDARD_NOT_FOUND");
+        match EMCStandard::get_standard("CISPR22", "ClassZ").unwrap_err() {
+            EmcError::ClassNotFound { available, .. } => assert!(available.contains(&"ClassB".to_string())),
+            other => panic!("unexpected {:?}", other),
+        }
+        
+        let mut standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        standard.dbuv_avg_limit_mask.pop();
+        let json = serde_json::to_string(&standard).unwrap();
+        assert_eq!(EMCStandard::from_json(&json).unwrap_err().code(), "LENGTH_MISMATCH");
+        standard.f_avg_limit_mask.pop();
+        standard.f_avg_limit_mask.reverse();
+        let json = serde_json::to_string(&standard).unwrap();
+        assert_eq!(EMCStandard::from_json(&json).unwrap_err().code(), "INVALID_MASK");
+    }
+    
+    #[test]
+    fn test_mismatched_and_invalid_traces_are_rejected() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        
+        // No silent truncation to the shorter array
+        let error = standard.check_compliance(&[1e6, 2e6, 3e6], &[40.0, 41.0], Detector::Qp).unwrap_err();
+        assert_eq!(error.code(), "LENGTH_MISMATCH");
+        
+        // NaN levels and unsorted frequencies are rejected rather than compared
+        let error = standard.check_compliance(&[150e3, 1e6], &[50.0, f64::NAN], Detector::Qp).unwrap_err();
+        assert_eq!(error, EmcError::InvalidPoint { index: 1, issue: validation::PointIssue::NonFiniteAmplitude });
+        let error = standard.check_compliance(&[2e6, 150e3], &[40.0, 50.0], Detector::Qp).unwrap_err();
+        assert_eq!(error, EmcError::InvalidPoint { index: 1, issue: validation::PointIssue::OutOfOrder });
+        
+        // Empty input is an error instead of NaN statistics, NaN levels no longer panic
+        assert!(standard.analyze_measurement_statistics(&[], &[]).is_err());
+        let error = standard.analyze_measurement_statistics(&[1e6, 2e6], &[40.0, f64::NAN]).unwrap_err();
+        assert_eq!(error.code(), "INVALID_POINT");
+        
+        // Order does not affect statistics
+        let stats = standard.analyze_measurement_statistics(&[2e6, 1e6], &[80.0, 40.0]).unwrap();
+        assert_eq!((stats.violation_count, stats.max_violation_frequency), (1, 2e6));
+    }
+    
+    #[test]
+    fn test_per_detector_statistics() {
+        // CISPR 22 Class B at 1 MHz: AVG 56, QP 62, PK 74 dBµV
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let frequencies = [1e6, 2e6];
+        let amplitudes = [60.0, 50.0];
+        
+        let peak = standard.analyze_detector_statistics(&frequencies, &amplitudes, "PK".parse().unwrap()).unwrap();
+        assert_eq!((peak.detector.as_str(), peak.violation_count, peak.compliance_rate), ("pk", 0, 100.0));
+        
+        let by_detector: Vec<(&str, usize, f64)> =
+            peak.detectors.iter().map(|d| (d.detector.as_str(), d.summary.violations, d.summary.worst_margin)).collect();
+        assert_eq!(by_detector, vec![("avg", 1, -4.0), ("qp", 0, 2.0), ("pk", 0, 14.0)]);
+        assert_eq!(peak.detectors[0].compliance_rate, 50.0);
+        
+        // The two-argument form keeps comparing against AVG
+        let avg = standard.analyze_measurement_statistics(&frequencies, &amplitudes).unwrap();
+        assert_eq!((avg.violation_count, avg.max_violation, avg.max_violation_frequency), (1, 4.0, 1e6));
+        
+        assert_eq!("rms".parse::<Detector>().unwrap_err().code(), "INVALID_DETECTOR");
+        
+        // No peak limit anywhere in FCC Part 15 conducted: no rate rather than 100 %
+        let fcc = EMCStandard::get_standard("FCC_Part15_Conducted", "ClassB").unwrap();
+        let peak = fcc.analyze_detector_statistics(&frequencies, &[90.0, 90.0], Detector::Pk).unwrap();
+        assert_eq!((peak.violation_count, peak.detectors[2].summary.points_with_limit), (0, 0));
+        assert!(peak.compliance_rate.is_nan());
+        assert_eq!(peak.detectors[1].compliance_rate, 0.0);
+    }
 }
@@ -0,0 +1,554 @@
+// wasm/src/limit_lines.rs
+// Limit-line import/export: CSV tables and receiver ASCII limit lines <-> StandardClass
+use serde::{Deserialize, Serialize};
+
+use crate::limit_ops::STEP;
+use crate::{Detector, EMCStandard, EmcError, LimitUnit, StandardClass};
+
+// Log-spaced points per decade used to reproduce linear-frequency interpolation
+const LINEAR_RESAMPLE_PPD: f64 = 50.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitLineFormat {
+    Csv,            // header row + frequency column + one column per detector
+    ReceiverAscii,  // "Key;Value;" header lines followed by "frequency;level;" rows, one detector
+}
+
+// How the source interpolates between points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InterpolationMode {
+    LogFrequency,
+    LinearFrequency,
+}
+
+impl LimitLineFormat {
+    pub fn parse(name: &str) -> Result<LimitLineFormat, EmcError> {
+        match name.to_lowercase().as_str() {
+            "csv" => Ok(LimitLineFormat::Csv),
+            "receiver" | "ascii" | "receiver_ascii" => Ok(LimitLineFormat::ReceiverAscii),
+            _ => Err(EmcError::invalid("Limit line format must be 'csv' or 'receiver'")),
+        }
+    }
+}
+
+impl InterpolationMode {
+    pub fn parse(name: &str) -> Result<InterpolationMode, EmcError> {
+        match name.to_lowercase().as_str() {
+            "log" | "logarithmic" => Ok(InterpolationMode::LogFrequency),
+            "lin" | "linear" => Ok(InterpolationMode::LinearFrequency),
+            _ => Err(EmcError::invalid("Interpolation must be 'log' or 'linear'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportOptions {
+    pub format: LimitLineFormat,
+    pub name: String,
+    #[serde(default)]
+    pub detector: Option<Detector>,                 // for single-level files without a detector; default avg
+    #[serde(default)]
+    pub interpolation: Option<InterpolationMode>,   // overrides the file's scaling
+    #[serde(default)]
+    pub frequency_unit: Option<String>,             // overrides unit detection, e.g. "MHz"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedLimits {
+    pub class: StandardClass,
+    pub frequency_unit: String,
+    pub interpolation: InterpolationMode,
+    pub warnings: Vec<String>,
+}
+
+pub(crate) fn frequency_scale(text: &str) -> Option<(f64, &'static str)> {
+    let text = text.to_lowercase();
+    [("ghz", 1e9, "GHz"), ("mhz", 1e6, "MHz"), ("khz", 1e3, "kHz"), ("hz", 1.0, "Hz")]
+        .iter()
+        .find(|(token, _, _)| text.contains(token))
+        .map(|&(_, scale, unit)| (scale, unit))
+}
+
+fn level_unit(text: &str) -> Option<LimitUnit> {
+    let text: String = text.to_lowercase().replace(['µ', 'μ'], "u").split_whitespace().collect();
+    if text.contains("dbuv/m") {
+        Some(LimitUnit::DbMicroVoltPerMeter)
+    } else if text.contains("dbua") {
+        Some(LimitUnit::DbMicroAmp)
+    } else if text.contains("dbuv") {
+        Some(LimitUnit::DbMicroVolt)
+    } else {
+        None
+    }
+}
+
+fn unit_label(unit: LimitUnit) -> &'static str {
+    match unit {
+        LimitUnit::DbMicroVolt => "dBuV",
+        LimitUnit::DbMicroAmp => "dBuA",
+        LimitUnit::DbMicroVoltPerMeter => "dBuV/m",
+    }
+}
+
+pub(crate) fn detector_of(text: &str) -> Option<Detector> {
+    let text = text.to_lowercase();
+    if text.contains("qp") || text.contains("quasi") {
+        Some(Detector::Qp)
+    } else if text.contains("pk") || text.contains("peak") {
+        Some(Detector::Pk)
+    } else if text.contains("av") {
+        Some(Detector::Avg)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn split_fields(line: &str) -> Vec<&str> {
+    let fields: Vec<&str> = if line.contains(';') {
+        line.split(';').collect()
+    } else if line.contains(',') {
+        line.split(',').collect()
+    } else if line.contains('\t') {
+        line.split('\t').collect()
+    } else {
+        line.split_whitespace().collect()
+    };
+    let mut fields: Vec<&str> = fields.into_iter().map(str::trim).collect();
+    while fields.last() == Some(&"") {
+        fields.pop();
+    }
+    fields
+}
+
+// 1-based character column of a field taken from `raw` (split_fields returns sub-slices)
+pub(crate) fn column_of(raw: &str, field: &str) -> Option<usize> {
+    let offset = (field.as_ptr() as usize).checked_sub(raw.as_ptr() as usize)?;
+    raw.get(..offset).map(|before| before.chars().count() + 1)
+}
+
+pub(crate) fn parse_number(field: &str, raw: &str, line: usize) -> Result<Option<f64>, EmcError> {
+    if field.is_empty() {
+        return Ok(None);
+    }
+    field
+        .parse::<f64>()
+        .map(Some)
+        .map_err(|_| EmcError::parse(line, column_of(raw, field), format!("'{}' is not a number", field)))
+}
+
+// Raw per-detector points (file frequency units) plus whatever the file says about itself
+#[derive(Default)]
+struct ParsedLine {
+    points: [Vec<(usize, f64, f64)>; 3],  // (line, frequency, level)
+    frequency_hint: Option<(f64, &'static str)>,
+    unit: Option<LimitUnit>,
+    interpolation: Option<InterpolationMode>,
+    warnings: Vec<String>,
+}
+
+fn parse_csv(text: &str, default_detector: Detector) -> Result<ParsedLine, EmcError> {
+    let mut parsed = ParsedLine::default();
+    let mut columns: Option<Vec<Option<Detector>>> = None;  // detector per level column
+
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if trimmed.starts_with('#') || trimmed.starts_with("//") {
+            parsed.frequency_hint = parsed.frequency_hint.or_else(|| frequency_scale(trimmed));
+            parsed.unit = parsed.unit.or_else(|| level_unit(trimmed));
+            continue;
+        }
+
+        let fields = split_fields(trimmed);
+        if fields[0].parse::<f64>().is_err() {
+            if columns.is_some() {
+                return Err(EmcError::parse(line, None, "unexpected header after data rows"));
+            }
+            parsed.frequency_hint = frequency_scale(fields[0]);
+            parsed.unit = fields[1..].iter().find_map(|f| level_unit(f)).or(parsed.unit);
+            let mut detectors: Vec<Option<Detector>> = fields[1..].iter().map(|f| detector_of(f)).collect();
+            // A single unnamed level column takes the default detector
+            if detectors.len() == 1 && detectors[0].is_none() {
+                detectors[0] = Some(default_detector);
+            }
+            columns = Some(detectors);
+            continue;
+        }
+
+        let columns = columns.get_or_insert_with(|| {
+            let mut cols = vec![Some(default_detector)];
+            cols.extend((2..fields.len()).map(|_| None));
+            cols
+        });
+        let frequency = parse_number(fields[0], raw, line)?.unwrap_or(f64::NAN);
+        for (column, detector) in columns.iter().enumerate() {
+            let Some(detector) = detector else { continue };
+            if let Some(level) = parse_number(fields.get(column + 1).copied().unwrap_or(""), raw, line)? {
+                parsed.points[detector.index()].push((line, frequency, level));
+            }
+        }
+    }
+
+    if let Some(columns) = columns {
+        if columns.iter().all(Option::is_none) {
+            return Err(EmcError::parse(1, None, "No AVG/QP/PK column found in the header"));
+        }
+    }
+    Ok(parsed)
+}
+
+fn parse_receiver_ascii(text: &str, default_detector: Detector) -> Result<ParsedLine, EmcError> {
+    let mut parsed = ParsedLine::default();
+    let mut detector = default_detector;
+    let mut explicit_detector = false;  // a Detector line overrides any hint in Name/Comment
+    let mut expected_values = None;
+    let mut values = 0;
+
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let fields = split_fields(raw.trim());
+        if fields.is_empty() {
+            continue;
+        }
+
+        if fields[0].parse::<f64>().is_ok() {
+            let frequency = parse_number(fields[0], raw, line)?.unwrap_or(f64::NAN);
+            let level = parse_number(fields.get(1).copied().unwrap_or(""), raw, line)?
+                .ok_or_else(|| EmcError::parse(line, None, "missing level"))?;
+            parsed.points[detector.index()].push((line, frequency, level));
+            values += 1;
+            continue;
+        }
+
+        let value = fields.get(1).copied().unwrap_or("");
+        match fields[0].to_lowercase().as_str() {
+            "unit" | "y-unit" => {
+                parsed.unit = Some(level_unit(value).ok_or_else(|| {
+                    EmcError::parse(line, column_of(raw, value), format!("unsupported level unit '{}'", value))
+                })?)
+            }
+            "x-unit" => parsed.frequency_hint = frequency_scale(value),
+            "detector" => {
+                detector = detector_of(value)
+                    .ok_or_else(|| EmcError::parse(line, column_of(raw, value), format!("unknown detector '{}'", value)))?;
+                explicit_detector = true;
+            }
+            "name" | "comment" if !explicit_detector => {
+                if let Some(d) = detector_of(value) {
+                    detector = d;
+                }
+            }
+            "scaling" => {
+                parsed.interpolation = Some(if value.to_lowercase().starts_with("lin") {
+                    InterpolationMode::LinearFrequency
+                } else {
+                    InterpolationMode::LogFrequency
+                })
+            }
+            "values" => expected_values = value.parse::<usize>().ok(),
+            _ => {}
+        }
+    }
+
+    if let Some(expected) = expected_values {
+        if expected != values {
+            parsed.warnings.push(format!("Header announces {} values, file has {}", expected, values));
+        }
+    }
+    Ok(parsed)
+}
+
+// Log-spaced points reproducing a linear-in-frequency segment under log interpolation
+fn resample_linear(mask: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut result: Vec<(f64, f64)> = mask.first().cloned().into_iter().collect();
+    for pair in mask.windows(2) {
+        let ((f1, a1), (f2, a2)) = (pair[0], pair[1]);
+        let steps = ((f2 / f1).log10() * LINEAR_RESAMPLE_PPD).ceil() as usize;
+        if a1 != a2 && steps > 1 {
+            for k in 1..steps {
+                let f = f1 * (f2 / f1).powf(k as f64 / steps as f64);
+                result.push((f, a1 + (a2 - a1) * (f - f1) / (f2 - f1)));
+            }
+        }
+        result.push((f2, a2));
+    }
+    result
+}
+
+// Validate and convert raw points: finite, positive, non-decreasing frequency (repeats become steps)
+fn build_mask(points: &[(usize, f64, f64)], scale: f64) -> Result<Vec<(f64, f64)>, EmcError> {
+    let mut mask: Vec<(f64, f64)> = Vec::with_capacity(points.len());
+    for &(line, frequency, level) in points {
+        if !frequency.is_finite() || frequency <= 0.0 {
+            return Err(EmcError::parse(line, None, "frequency must be positive"));
+        }
+        if !level.is_finite() {
+            return Err(EmcError::parse(line, None, "level must be finite"));
+        }
+
+        let mut frequency = frequency * scale;
+        if let Some(&(previous, _)) = mask.last() {
+            if frequency < previous * (1.0 - 1e-12) {
+                return Err(EmcError::parse(line, None, "frequencies must be in ascending order"));
+            }
+            if frequency <= previous {
+                frequency = previous * (1.0 + STEP);
+            }
+        }
+        mask.push((frequency, level));
+    }
+    Ok(mask)
+}
+
+pub fn import_limit_line(text: &str, options: &ImportOptions) -> Result<ImportedLimits, EmcError> {
+    let default_detector = options.detector.unwrap_or_default();
+
+    let mut parsed = match options.format {
+        LimitLineFormat::Csv => parse_csv(text, default_detector)?,
+        LimitLineFormat::ReceiverAscii => parse_receiver_ascii(text, default_detector)?,
+    };
+
+    let max_frequency = parsed.points.iter().flatten().map(|p| p.1).fold(0.0, f64::max);
+    let (scale, frequency_unit) = match &options.frequency_unit {
+        Some(unit) => frequency_scale(unit).ok_or_else(|| EmcError::invalid(format!("Unknown frequency unit '{}'", unit)))?,
+        None => match parsed.frequency_hint {
+            Some(hint) => hint,
+            None if max_frequency < 1e4 => {
+                parsed.warnings.push("No frequency unit given; values look like MHz".to_string());
+                (1e6, "MHz")
+            }
+            None => {
+                parsed.warnings.push("No frequency unit given; assuming Hz".to_string());
+                (1.0, "Hz")
+            }
+        },
+    };
+    let unit = parsed.unit.unwrap_or_else(|| {
+        parsed.warnings.push("No level unit given; assuming dBuV".to_string());
+        LimitUnit::DbMicroVolt
+    });
+    let interpolation = options.interpolation.or(parsed.interpolation).unwrap_or(InterpolationMode::LogFrequency);
+
+    let mut masks: Vec<Option<Vec<(f64, f64)>>> = Vec::new();
+    for (detector, points) in Detector::ALL.into_iter().zip(&parsed.points) {
+        if points.is_empty() {
+            masks.push(None);
+            continue;
+        }
+        if points.len() < 2 {
+            return Err(EmcError::InvalidMask { mask: detector.to_string(), reason: "at least two points are needed".to_string() });
+        }
+        let mask = build_mask(points, scale)?;
+        masks.push(Some(match interpolation {
+            InterpolationMode::LogFrequency => mask,
+            InterpolationMode::LinearFrequency => resample_linear(&mask),
+        }));
+    }
+
+    if masks.iter().all(Option::is_none) {
+        return Err(EmcError::invalid("No limit points found"));
+    }
+    if masks[0].is_none() {
+        parsed.warnings.push("No AVG limit in the file; AVG checks against this line are not meaningful".to_string());
+    }
+
+    let class = StandardClass {
+        name: options.name.clone(),
+        description: format!("Imported limit line ({:?})", options.format),
+        unit,
+        avg_mask: masks[0].take().unwrap_or_default(),
+        qp_mask: masks[1].take(),
+        pk_mask: masks[2].take(),
+        bands: None,
+        detector_rules: None,
+        variants: None,
+        metadata: None,
+        edition: None,
+    };
+
+    Ok(ImportedLimits { class, frequency_unit: frequency_unit.to_string(), interpolation, warnings: parsed.warnings })
+}
+
+fn detector_column(standard: &EMCStandard, detector: Detector) -> bool {
+    match detector {
+        Detector::Avg => !standard.f_avg_limit_mask.is_empty() || standard.bands.is_some(),
+        Detector::Qp => standard.f_qp_limit_mask.is_some() || standard.bands.as_ref().is_some_and(|b| b.iter().any(|b| b.qp.is_some())),
+        Detector::Pk => standard.f_pk_limit_mask.is_some() || standard.bands.as_ref().is_some_and(|b| b.iter().any(|b| b.pk.is_some())),
+    }
+}
+
+// Breakpoints plus a step pair at every band edge, so band limits stay flat after re-import.
+// Rows without a limit (gaps between bands) are left out by the writers.
+fn export_grid(standard: &EMCStandard) -> Vec<f64> {
+    let (lo, hi) = standard.frequency_span();
+    let mut grid = standard.breakpoints();
+    for band in standard.bands.iter().flatten() {
+        for edge in [band.f_start, band.f_stop] {
+            grid.extend([edge * (1.0 - STEP), edge * (1.0 + STEP)]);
+        }
+    }
+    grid.retain(|&f| f >= lo && f <= hi);
+    grid.sort_by(|a, b| a.total_cmp(b));
+    grid.dedup();
+    grid
+}
+
+fn limit_of(standard: &EMCStandard, frequency: f64, detector: Detector) -> f64 {
+    standard.interp_log(frequency).for_detector(detector)
+}
+
+// CSV with every defined detector, or a receiver limit line for one detector (default QP if defined)
+pub fn export_limit_line(standard: &EMCStandard, format: LimitLineFormat, detector: Option<Detector>) -> String {
+    let grid = export_grid(standard);
+    let unit = unit_label(standard.unit);
+
+    match format {
+        LimitLineFormat::Csv => {
+            let columns: Vec<Detector> = Detector::ALL.into_iter().filter(|&d| detector_column(standard, d)).collect();
+            let mut out = format!("# {}\nFrequency (Hz)", standard.name);
+            for &d in &columns {
+                out.push_str(&format!(",{} ({})", d.as_str().to_uppercase(), unit));
+            }
+            out.push('\n');
+            for &f in &grid {
+                let levels: Vec<f64> = columns.iter().map(|&d| limit_of(standard, f, d)).collect();
+                if levels.iter().all(|level| level.is_nan()) {
+                    continue;
+                }
+                out.push_str(&f.to_string());
+                for level in levels {
+                    out.push(',');
+                    if !level.is_nan() {
+                        out.push_str(&level.to_string());
+                    }
+                }
+                out.push('\n');
+            }
+            out
+        }
+        LimitLineFormat::ReceiverAscii => {
+            let d = match detector {
+                Some(detector) => detector,
+                None if detector_column(standard, Detector::Qp) => Detector::Qp,
+                None => Detector::Avg,
+            };
+            let rows: Vec<(f64, f64)> =
+                grid.iter().map(|&f| (f, limit_of(standard, f, d))).filter(|(_, level)| !level.is_nan()).collect();
+
+            let mut out = format!(
+                "Type;LIMIT LINE;\nName;{};\nDetector;{};\nDomain;FREQUENCY;\nX-Unit;Hz;\nUnit;{};\nScaling;LOGARITHMIC;\nValues;{};\n",
+                standard.name,
+                d.as_str().to_uppercase(),
+                unit,
+                rows.len()
+            );
+            for (f, level) in rows {
+                out.push_str(&format!("{};{};\n", f, level));
+            }
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(format: LimitLineFormat) -> ImportOptions {
+        ImportOptions { format, name: "Imported".to_string(), detector: None, interpolation: None, frequency_unit: None }
+    }
+
+    #[test]
+    fn test_csv_unit_detection() {
+        let text = "Frequency (MHz);QP (dBµV/m);AVG (dBµV/m)\n30;40;30\n230;40;30\n230;47;37\n1000;47;37\n";
+        let imported = import_limit_line(text, &options(LimitLineFormat::Csv)).unwrap();
+
+        assert_eq!(imported.frequency_unit, "MHz");
+        assert_eq!(imported.class.unit, LimitUnit::DbMicroVoltPerMeter);
+        let standard = EMCStandard::from_standard_class(&imported.class);
+        assert_eq!(standard.interp_log(100e6).dbuv_qp_limit, 40.0);
+        assert_eq!(standard.interp_log(500e6).dbuv_avg_limit, 37.0);
+        assert!(imported.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let unsorted = "Frequency (Hz),AVG (dBuV)\n150000,66\n100000,56\n";
+        assert!(import_limit_line(unsorted, &options(LimitLineFormat::Csv)).unwrap_err().to_string().contains("line 3"));
+
+        let bad = "Frequency (Hz),AVG (dBuV)\n150000,66\n500000,abc\n";
+        assert_eq!(
+            import_limit_line(bad, &options(LimitLineFormat::Csv)).unwrap_err(),
+            EmcError::parse(3, Some(8), "'abc' is not a number")
+        );
+
+        // Bare two-column file: MHz guessed, dBuV assumed, both reported
+        let bare = "0.15 66\n30 60\n";
+        let imported = import_limit_line(bare, &options(LimitLineFormat::Csv)).unwrap();
+        assert_eq!(imported.class.avg_mask[1].0, 30e6);
+        assert_eq!(imported.warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_linear_interpolation_mode() {
+        let text = "Type;LIMIT LINE;\nName;Customer QP;\nUnit;DBUV;\nX-Unit;MHz;\nScaling;LINEAR;\n1;60;\n31;30;\n";
+        let imported = import_limit_line(text, &options(LimitLineFormat::ReceiverAscii)).unwrap();
+
+        assert_eq!(imported.interpolation, InterpolationMode::LinearFrequency);
+        assert!(imported.class.qp_mask.is_some());
+        let standard = EMCStandard::from_standard_class(&imported.class);
+        // Linear in frequency: halfway is 16 MHz -> 45 dBµV
+        assert!((standard.interp_log(16e6).dbuv_qp_limit - 45.0).abs() < 0.05);
+
+        // An explicit detector wins over a detector-like name
+        let text = "Type;LIMIT LINE;\nDetector;QP;\nName;Peak limit;\nUnit;DBUV;\n1e6;60;\n30e6;30;\n";
+        let imported = import_limit_line(text, &options(LimitLineFormat::ReceiverAscii)).unwrap();
+        assert!(imported.class.qp_mask.is_some() && imported.class.pk_mask.is_none());
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+
+        let csv = export_limit_line(&standard, LimitLineFormat::Csv, None);
+        let imported = EMCStandard::from_standard_class(&import_limit_line(&csv, &options(LimitLineFormat::Csv)).unwrap().class);
+
+        let receiver = export_limit_line(&standard, LimitLineFormat::ReceiverAscii, Some(Detector::Pk));
+        let peak = EMCStandard::from_standard_class(&import_limit_line(&receiver, &options(LimitLineFormat::ReceiverAscii)).unwrap().class);
+
+        for &f in &[200e3, 1e6, 20e6, 500e6] {
+            let original = standard.interp_log(f);
+            let reloaded = imported.interp_log(f);
+            assert!((original.dbuv_avg_limit - reloaded.dbuv_avg_limit).abs() < 1e-9);
+            assert!((original.dbuv_qp_limit - reloaded.dbuv_qp_limit).abs() < 1e-9);
+            assert!((original.dbuv_pk_limit - peak.interp_log(f).dbuv_pk_limit).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_band_table_export_round_trip() {
+        let standard = EMCStandard::get_standard("CISPR25_Conducted_Voltage", "Class3").unwrap();
+        let csv = export_limit_line(&standard, LimitLineFormat::Csv, None);
+        let imported = EMCStandard::from_standard_class(&import_limit_line(&csv, &options(LimitLineFormat::Csv)).unwrap().class);
+        let receiver = export_limit_line(&standard, LimitLineFormat::ReceiverAscii, Some(Detector::Pk));
+        let peak = EMCStandard::from_standard_class(&import_limit_line(&receiver, &options(LimitLineFormat::ReceiverAscii)).unwrap().class);
+
+        // Flat up to each band edge: no ramps between bands
+        for band in standard.bands.as_ref().unwrap() {
+            for f in [band.f_start * (1.0 + 10.0 * STEP), (band.f_start * band.f_stop).sqrt(), band.f_stop * (1.0 - 10.0 * STEP)] {
+                let original = standard.interp_log(f);
+                let reloaded = imported.interp_log(f);
+                assert!((original.dbuv_avg_limit - reloaded.dbuv_avg_limit).abs() < 1e-6, "{} Hz", f);
+                assert!((original.dbuv_pk_limit - peak.interp_log(f).dbuv_pk_limit).abs() < 1e-6, "{} Hz", f);
+                if !original.dbuv_qp_limit.is_nan() {
+                    assert!((original.dbuv_qp_limit - reloaded.dbuv_qp_limit).abs() < 1e-6, "{} Hz", f);
+                }
+            }
+        }
+        // Nothing is written inside the 300-530 kHz gap
+        assert!(!csv.lines().filter_map(|l| l.split(',').next()?.parse::<f64>().ok()).any(|f| f > 300.001e3 && f < 529.999e3));
+    }
+}
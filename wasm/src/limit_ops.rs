@@ -0,0 +1,323 @@
+// wasm/src/limit_ops.rs
+// Limit arithmetic: offsets, per-band offsets, truncation and splicing into new named standards
+use std::collections::HashMap;
+
+use crate::{log_interp, DetectorRule, EMCStandard, EmcError, EmcStandardsData, LimitBand};
+
+// Relative frequency shift used to draw a vertical step, as in the standards data (5e5 -> 5.00001e5)
+pub(crate) const STEP: f64 = 1e-6;
+
+type Mask = Vec<(f64, f64)>;
+
+fn mask_of(f: &[f64], dbuv: &[f64]) -> Mask {
+    f.iter().cloned().zip(dbuv.iter().cloned()).collect()
+}
+
+fn value_at(mask: &[(f64, f64)], frequency: f64) -> f64 {
+    let (f, dbuv): (Vec<f64>, Vec<f64>) = mask.iter().cloned().unzip();
+    log_interp(&f, &dbuv, frequency)
+}
+
+// Points inside [f_min, f_max], with interpolated end points where the mask is cut
+fn clip_mask(mask: &[(f64, f64)], f_min: f64, f_max: f64) -> Mask {
+    let (Some(&(first, _)), Some(&(last, _))) = (mask.first(), mask.last()) else {
+        return Vec::new();
+    };
+    let (lo, hi) = (f_min.max(first), f_max.min(last));
+    if lo > hi {
+        return Vec::new();
+    }
+
+    let mut clipped = vec![(lo, value_at(mask, lo))];
+    clipped.extend(mask.iter().filter(|&&(f, _)| f > lo && f < hi).cloned());
+    if hi > lo {
+        clipped.push((hi, value_at(mask, hi)));
+    }
+    clipped
+}
+
+// Add `offset` over [f_start, f_stop], with vertical steps at the range edges
+fn offset_mask_range(mask: &[(f64, f64)], f_start: f64, f_stop: f64, offset: f64) -> Mask {
+    let (Some(&(first, _)), Some(&(last, _))) = (mask.first(), mask.last()) else {
+        return Vec::new();
+    };
+
+    let mut frequencies: Vec<f64> = mask.iter().map(|&(f, _)| f).collect();
+    for edge in [f_start * (1.0 - STEP), f_start, f_stop, f_stop * (1.0 + STEP)] {
+        if edge > first && edge < last {
+            frequencies.push(edge);
+        }
+    }
+    frequencies.sort_by(|a, b| a.total_cmp(b));
+    frequencies.dedup();
+
+    frequencies
+        .into_iter()
+        .map(|f| {
+            let shift = if f >= f_start && f <= f_stop { offset } else { 0.0 };
+            (f, value_at(mask, f) + shift)
+        })
+        .collect()
+}
+
+fn shift(value: Option<f64>, offset: f64) -> Option<f64> {
+    value.map(|v| v + offset)
+}
+
+// Split bands at the range edges and offset the parts inside it
+fn offset_bands(bands: &[LimitBand], f_start: f64, f_stop: f64, offset: f64) -> Vec<LimitBand> {
+    let mut result = Vec::new();
+    for band in bands {
+        let mut edges = vec![band.f_start];
+        edges.extend([f_start, f_stop].iter().filter(|&&e| e > band.f_start && e < band.f_stop));
+        edges.push(band.f_stop);
+
+        for pair in edges.windows(2) {
+            let inside = pair[0] >= f_start && pair[1] <= f_stop;
+            let amount = if inside { offset } else { 0.0 };
+            result.push(LimitBand {
+                name: band.name.clone(),
+                f_start: pair[0],
+                f_stop: pair[1],
+                avg: shift(band.avg, amount),
+                qp: shift(band.qp, amount),
+                pk: shift(band.pk, amount),
+            });
+        }
+    }
+    result
+}
+
+fn clip_bands(bands: &[LimitBand], f_min: f64, f_max: f64) -> Vec<LimitBand> {
+    bands
+        .iter()
+        .filter(|band| band.f_stop >= f_min && band.f_start <= f_max)
+        .map(|band| LimitBand { f_start: band.f_start.max(f_min), f_stop: band.f_stop.min(f_max), ..band.clone() })
+        .collect()
+}
+
+fn renamed(standard: &EMCStandard, name: &str, description: String) -> EMCStandard {
+    EMCStandard { name: name.to_string(), description, edition: None, ..standard.clone() }
+}
+
+// Apply `f` to every present detector mask
+fn map_masks(standard: &mut EMCStandard, f: impl Fn(&[(f64, f64)]) -> Mask) {
+    let avg = f(&mask_of(&standard.f_avg_limit_mask, &standard.dbuv_avg_limit_mask));
+    (standard.f_avg_limit_mask, standard.dbuv_avg_limit_mask) = avg.into_iter().unzip();
+
+    for (freqs, levels) in [
+        (&mut standard.f_qp_limit_mask, &mut standard.dbuv_qp_limit_mask),
+        (&mut standard.f_pk_limit_mask, &mut standard.dbuv_pk_limit_mask),
+    ] {
+        if let (Some(fs), Some(ls)) = (freqs.as_mut(), levels.as_mut()) {
+            let mapped = f(&mask_of(fs, ls));
+            (*fs, *ls) = mapped.into_iter().unzip();
+        }
+    }
+}
+
+// Offset (dB, negative tightens) over [f_start, f_stop]; the whole span when no range is given
+pub fn offset_limits(standard: &EMCStandard, name: &str, offset: f64, range: Option<(f64, f64)>) -> Result<EMCStandard, EmcError> {
+    if !offset.is_finite() {
+        return Err(EmcError::invalid("Offset must be a finite number of dB"));
+    }
+    let (f_start, f_stop) = range.unwrap_or((0.0, f64::INFINITY));
+    if f_start >= f_stop {
+        return Err(EmcError::invalid(format!("Offset range start ({}) must be below its stop ({})", f_start, f_stop)));
+    }
+
+    let description = match range {
+        Some((lo, hi)) => format!("{} {:+} dB from {} Hz to {} Hz", standard.name, offset, lo, hi),
+        None => format!("{} {:+} dB", standard.name, offset),
+    };
+    let mut result = renamed(standard, name, description);
+
+    map_masks(&mut result, |mask| match range {
+        Some(_) => offset_mask_range(mask, f_start, f_stop, offset),
+        None => mask.iter().map(|&(f, v)| (f, v + offset)).collect(),
+    });
+    result.bands = standard.bands.as_ref().map(|bands| offset_bands(bands, f_start, f_stop, offset));
+    Ok(result)
+}
+
+// Keep only [f_min, f_max]
+pub fn truncate_limits(standard: &EMCStandard, name: &str, f_min: f64, f_max: f64) -> Result<EMCStandard, EmcError> {
+    let (lo, hi) = standard.frequency_span();
+    if f_min >= f_max || f_max < lo || f_min > hi {
+        return Err(EmcError::invalid(format!(
+            "Range {} Hz - {} Hz does not overlap '{}' ({} Hz - {} Hz)",
+            f_min, f_max, standard.name, lo, hi
+        )));
+    }
+
+    let description = format!("{} from {} Hz to {} Hz", standard.name, f_min, f_max);
+    let mut result = renamed(standard, name, description);
+    map_masks(&mut result, |mask| clip_mask(mask, f_min, f_max));
+    result.bands = standard.bands.as_ref().map(|bands| clip_bands(bands, f_min, f_max));
+    Ok(result)
+}
+
+// Detector mask, materialising the QP/PK fallbacks when only one side of a splice has them
+fn detector_mask(standard: &EMCStandard, detector: usize) -> Mask {
+    let explicit = match detector {
+        1 => standard.f_qp_limit_mask.as_ref().zip(standard.dbuv_qp_limit_mask.as_ref()),
+        2 => standard.f_pk_limit_mask.as_ref().zip(standard.dbuv_pk_limit_mask.as_ref()),
+        _ => Some((&standard.f_avg_limit_mask, &standard.dbuv_avg_limit_mask)),
+    };
+    match explicit {
+        Some((f, dbuv)) => mask_of(f, dbuv),
+        None => standard
+            .f_avg_limit_mask
+            .iter()
+            .map(|&f| {
+                let limits = standard.interp_log(f);
+                (f, if detector == 1 { limits.dbuv_qp_limit } else { limits.dbuv_pk_limit })
+            })
+            .filter(|&(_, limit)| !limit.is_nan())
+            .collect(),
+    }
+}
+
+// Rules restricted to [f_min, f_max]
+fn clip_rules(rules: &[DetectorRule], f_min: f64, f_max: f64) -> Vec<DetectorRule> {
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let lo = rule.f_start.unwrap_or(0.0).max(f_min);
+            let hi = rule.f_stop.unwrap_or(f64::INFINITY).min(f_max);
+            (lo <= hi).then(|| DetectorRule {
+                f_start: (lo > 0.0).then_some(lo),
+                f_stop: hi.is_finite().then_some(hi),
+                ..rule.clone()
+            })
+        })
+        .collect()
+}
+
+// `low` below the breakpoint and `high` from it upwards, with a step at the breakpoint
+pub fn splice_limits(low: &EMCStandard, high: &EMCStandard, name: &str, breakpoint: f64) -> Result<EMCStandard, EmcError> {
+    if low.unit != high.unit {
+        return Err(EmcError::UnitMismatch { context: format!("Splicing '{}' with '{}'", low.name, high.name), expected: low.unit, found: high.unit });
+    }
+    if low.bands.is_some() != high.bands.is_some() {
+        return Err(EmcError::invalid("Cannot splice band-table limits with mask limits"));
+    }
+    if !breakpoint.is_finite() || breakpoint <= 0.0 {
+        return Err(EmcError::invalid("Breakpoint must be a positive frequency in Hz"));
+    }
+    // Each side must keep part of its span
+    let ((low_start, _), (_, high_stop)) = (low.frequency_span(), high.frequency_span());
+    if breakpoint <= low_start || breakpoint > high_stop {
+        return Err(EmcError::invalid(format!(
+            "Breakpoint {} Hz must lie above the start of '{}' ({} Hz) and not above the end of '{}' ({} Hz)",
+            breakpoint, low.name, low_start, high.name, high_stop
+        )));
+    }
+
+    let description = format!("{} below {} Hz, {} above", low.name, breakpoint, high.name);
+    let mut result = renamed(low, name, description);
+
+    let mut rules = clip_rules(low.detector_rules.as_deref().unwrap_or_default(), 0.0, breakpoint * (1.0 - STEP));
+    rules.extend(clip_rules(high.detector_rules.as_deref().unwrap_or_default(), breakpoint, f64::INFINITY));
+    result.detector_rules = (!rules.is_empty()).then_some(rules);
+
+    if let (Some(low_bands), Some(high_bands)) = (&low.bands, &high.bands) {
+        let mut bands = clip_bands(low_bands, 0.0, breakpoint * (1.0 - STEP));
+        bands.extend(clip_bands(high_bands, breakpoint, f64::INFINITY));
+        result.bands = Some(bands);
+        return Ok(result);
+    }
+
+    let joined = |detector: usize| -> Mask {
+        // The limit at the breakpoint itself belongs to the upper standard
+        let mut mask = clip_mask(&detector_mask(low, detector), 0.0, breakpoint * (1.0 - STEP));
+        mask.extend(clip_mask(&detector_mask(high, detector), breakpoint, f64::INFINITY));
+        mask
+    };
+
+    (result.f_avg_limit_mask, result.dbuv_avg_limit_mask) = joined(0).into_iter().unzip();
+    let has_qp = low.f_qp_limit_mask.is_some() || high.f_qp_limit_mask.is_some();
+    let has_pk = low.f_pk_limit_mask.is_some() || high.f_pk_limit_mask.is_some();
+    (result.f_qp_limit_mask, result.dbuv_qp_limit_mask) = if has_qp {
+        let (f, dbuv) = joined(1).into_iter().unzip();
+        (Some(f), Some(dbuv))
+    } else {
+        (None, None)
+    };
+    (result.f_pk_limit_mask, result.dbuv_pk_limit_mask) = if has_pk {
+        let (f, dbuv) = joined(2).into_iter().unzip();
+        (Some(f), Some(dbuv))
+    } else {
+        (None, None)
+    };
+    Ok(result)
+}
+
+// Standards JSON document holding a single standard class
+pub fn to_standards_json(standard: &EMCStandard, standard_key: &str, class_key: &str) -> Result<String, EmcError> {
+    let classes = HashMap::from([(class_key.to_string(), standard.to_standard_class())]);
+    let data = EmcStandardsData { standards: HashMap::from([(standard_key.to_string(), classes)]) };
+    serde_json::to_string_pretty(&data).map_err(|e| EmcError::invalid(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_above_frequency() {
+        // "CISPR 22 Class B minus 6 dB above 30 MHz"
+        let base = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let derated = offset_limits(&base, "Customer", -6.0, Some((30e6, f64::INFINITY))).unwrap();
+
+        assert_eq!(derated.interp_log(10e6).dbuv_avg_limit, base.interp_log(10e6).dbuv_avg_limit);
+        assert!((derated.interp_log(30e6).dbuv_avg_limit - 24.0).abs() < 1e-9);
+        assert!((derated.interp_log(50e6).dbuv_qp_limit - (base.interp_log(50e6).dbuv_qp_limit - 6.0)).abs() < 1e-9);
+        assert!((derated.interp_log(29.9e6).dbuv_avg_limit - base.interp_log(29.9e6).dbuv_avg_limit).abs() < 1e-9);
+
+        let flat = offset_limits(&base, "Flat", 3.0, None).unwrap();
+        assert_eq!(flat.interp_log(1e6).dbuv_avg_limit, 59.0);
+        assert!(offset_limits(&base, "Bad", f64::NAN, None).is_err());
+    }
+
+    #[test]
+    fn test_band_offset_and_truncation() {
+        let base = EMCStandard::get_standard("CISPR25_Radiated_ALSE", "Class5").unwrap();
+        let derated = offset_limits(&base, "Class 5 - 6 dB", -6.0, Some((30e6, f64::INFINITY))).unwrap();
+        let fm = 100e6;
+        assert_eq!(derated.interp_log(fm).dbuv_avg_limit, base.interp_log(fm).dbuv_avg_limit - 6.0);
+        assert_eq!(derated.interp_log(1e6).dbuv_avg_limit, base.interp_log(1e6).dbuv_avg_limit);
+
+        let truncated = truncate_limits(&base, "FM only", 76e6, 108e6).unwrap();
+        assert_eq!(truncated.frequency_span(), (76e6, 108e6));
+        assert!(truncate_limits(&base, "None", 5e9, 6e9).is_err());
+
+        let mains = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let conducted = truncate_limits(&mains, "Conducted", 150e3, 30e6).unwrap();
+        assert_eq!(conducted.frequency_span(), (150e3, 30e6));
+        assert!((conducted.interp_log(1e6).dbuv_qp_limit - 62.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_splice_and_json_round_trip() {
+        let low = EMCStandard::get_standard("CISPR22", "ClassA").unwrap();
+        let high = EMCStandard::get_standard("EN55032", "ClassB").unwrap();
+        let spliced = splice_limits(&low, &high, "Hybrid", 30e6).unwrap();
+
+        assert!((spliced.interp_log(1e6).dbuv_avg_limit - low.interp_log(1e6).dbuv_avg_limit).abs() < 1e-9);
+        assert_eq!(spliced.interp_log(30e6).dbuv_avg_limit, high.interp_log(30e6).dbuv_avg_limit);
+        assert_eq!(spliced.interp_log(200e6).dbuv_pk_limit, high.interp_log(200e6).dbuv_pk_limit);
+
+        let json = to_standards_json(&spliced, "Hybrid", "Custom").unwrap();
+        let data: EmcStandardsData = serde_json::from_str(&json).unwrap();
+        let reloaded = EMCStandard::from_standard_class(&data.standards["Hybrid"]["Custom"]);
+        assert_eq!(reloaded.f_avg_limit_mask, spliced.f_avg_limit_mask);
+        assert_eq!(reloaded.dbuv_qp_limit_mask, spliced.dbuv_qp_limit_mask);
+
+        let current = EMCStandard::get_standard("CISPR32_Telecom_Current", "ClassB").unwrap();
+        assert_eq!(splice_limits(&low, &current, "Bad", 30e6).unwrap_err().code(), "UNIT_MISMATCH");
+        for breakpoint in [f64::NAN, 0.0, -30e6, 100e3, 2e9] {
+            assert_eq!(splice_limits(&low, &high, "Bad", breakpoint).unwrap_err().code(), "INVALID_INPUT", "{}", breakpoint);
+        }
+    }
+}
@@ -0,0 +1,187 @@
+// wasm/src/lisn.rs
+// LISN / artificial network models: impedance, voltage division factor and current-to-voltage conversion
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::frequency_helpers::standard_definitions::create_log_range;
+use crate::transducers::TransducerTable;
+use crate::EmcError;
+
+// Receiver input impedance (Ω)
+const RECEIVER_IMPEDANCE: f64 = 50.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LisnModel {
+    Cispr16_50uH,  // CISPR 16-1-2 V-network, 50 Ω || (50 µH + 5 Ω)
+    Cispr25_5uH,   // CISPR 25 automotive artificial network, 50 Ω || 5 µH
+}
+
+// Idealised network: EUT port sees the receiver branch in parallel with the supply branch
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LisnParameters {
+    pub inductance: f64,            // H, supply-side inductor
+    pub series_resistance: f64,     // Ω, in series with the inductor
+    pub coupling_capacitance: f64,  // F, between EUT port and receiver
+    pub discharge_resistance: f64,  // Ω, across the receiver port
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LisnImpedancePoint {
+    pub frequency: f64,
+    pub magnitude: f64,  // Ω
+    pub phase: f64,      // degrees
+    pub vdf: f64,        // dB, EUT-port voltage over receiver voltage
+}
+
+// Minimal complex helpers as (re, im)
+fn c_add(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+fn c_mul(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+fn c_div(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    let d = b.0 * b.0 + b.1 * b.1;
+    ((a.0 * b.0 + a.1 * b.1) / d, (a.1 * b.0 - a.0 * b.1) / d)
+}
+
+fn c_parallel(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    c_div(c_mul(a, b), c_add(a, b))
+}
+
+fn c_abs(a: (f64, f64)) -> f64 {
+    a.0.hypot(a.1)
+}
+
+impl LisnModel {
+    pub fn parse(name: &str) -> Result<LisnModel, EmcError> {
+        match name.to_lowercase().as_str() {
+            "cispr16_50uh" | "cispr16" | "50uh" => Ok(LisnModel::Cispr16_50uH),
+            "cispr25_5uh" | "cispr25" | "5uh" => Ok(LisnModel::Cispr25_5uH),
+            _ => Err(EmcError::invalid("LISN model must be 'cispr16_50uh' or 'cispr25_5uh'")),
+        }
+    }
+
+    pub fn parameters(&self) -> LisnParameters {
+        match self {
+            LisnModel::Cispr16_50uH => LisnParameters {
+                inductance: 50e-6,
+                series_resistance: 5.0,
+                coupling_capacitance: 0.25e-6,
+                discharge_resistance: 1_000.0,
+            },
+            LisnModel::Cispr25_5uH => LisnParameters {
+                inductance: 5e-6,
+                series_resistance: 0.0,
+                coupling_capacitance: 0.1e-6,
+                discharge_resistance: 1_000.0,
+            },
+        }
+    }
+
+    // Receiver branch: coupling capacitor in series with (receiver || discharge resistor)
+    fn receiver_branch(&self, frequency: f64) -> ((f64, f64), (f64, f64)) {
+        let p = self.parameters();
+        let omega = 2.0 * PI * frequency;
+        let termination = (RECEIVER_IMPEDANCE * p.discharge_resistance / (RECEIVER_IMPEDANCE + p.discharge_resistance), 0.0);
+        let coupling = (0.0, -1.0 / (omega * p.coupling_capacitance));
+        (c_add(coupling, termination), termination)
+    }
+
+    // Complex impedance seen by the EUT
+    pub fn impedance(&self, frequency: f64) -> (f64, f64) {
+        let p = self.parameters();
+        let omega = 2.0 * PI * frequency;
+        let supply = (p.series_resistance, omega * p.inductance);
+        let (receiver, _) = self.receiver_branch(frequency);
+        c_parallel(supply, receiver)
+    }
+
+    // Voltage division factor (dB) between EUT port and receiver input
+    pub fn vdf(&self, frequency: f64) -> f64 {
+        let (branch, termination) = self.receiver_branch(frequency);
+        20.0 * (c_abs(branch) / c_abs(termination)).log10()
+    }
+
+    pub fn impedance_table(&self, f_min: f64, f_max: f64, points_per_decade: usize) -> Vec<LisnImpedancePoint> {
+        create_log_range(f_min, f_max, points_per_decade)
+            .into_iter()
+            .map(|frequency| {
+                let z = self.impedance(frequency);
+                LisnImpedancePoint {
+                    frequency,
+                    magnitude: c_abs(z),
+                    phase: z.1.atan2(z.0).to_degrees(),
+                    vdf: self.vdf(frequency),
+                }
+            })
+            .collect()
+    }
+
+    // VDF as a transducer, turning receiver readings into EUT-port voltages
+    pub fn vdf_transducer(&self, f_min: f64, f_max: f64, points_per_decade: usize) -> TransducerTable {
+        let points = create_log_range(f_min, f_max, points_per_decade)
+            .into_iter()
+            .map(|frequency| (frequency, self.vdf(frequency)))
+            .collect();
+        TransducerTable::new(&format!("{:?} VDF", self), points)
+    }
+
+    // Receiver reading (dBµV) produced by a noise-source current (dBµA) driven into the LISN
+    pub fn current_to_voltage(&self, frequencies: &[f64], current_dbua: &[f64]) -> Result<Vec<f64>, EmcError> {
+        if frequencies.len() != current_dbua.len() {
+            return Err(EmcError::LengthMismatch { field: "currents".to_string(), expected: frequencies.len(), found: current_dbua.len() });
+        }
+
+        Ok(frequencies
+            .iter()
+            .zip(current_dbua.iter())
+            .map(|(&f, &i)| i + 20.0 * c_abs(self.impedance(f)).log10() - self.vdf(f))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cispr16_impedance_curve() {
+        let lisn = LisnModel::Cispr16_50uH;
+
+        // Low frequencies: dominated by the 5 Ω + 50 µH branch; high frequencies: 50 Ω
+        let low = c_abs(lisn.impedance(9e3));
+        assert!(low > 4.0 && low < 6.5, "|Z(9 kHz)| = {}", low);
+        let high = c_abs(lisn.impedance(30e6));
+        assert!((high - 47.6).abs() < 1.0, "|Z(30 MHz)| = {}", high);
+    }
+
+    #[test]
+    fn test_vdf_small_in_band() {
+        for lisn in [LisnModel::Cispr16_50uH, LisnModel::Cispr25_5uH] {
+            assert!(lisn.vdf(1e6) < 0.5);
+            assert!(lisn.vdf(1e6) >= 0.0);
+        }
+        // Coupling capacitor starts to matter at low frequency
+        assert!(LisnModel::Cispr25_5uH.vdf(10e3) > LisnModel::Cispr25_5uH.vdf(1e6));
+    }
+
+    #[test]
+    fn test_current_to_voltage() {
+        let lisn = LisnModel::Cispr16_50uH;
+        // 0 dBµA into ~47.6 Ω at 30 MHz -> ~33.5 dBµV
+        let v = lisn.current_to_voltage(&[30e6], &[0.0]).unwrap();
+        assert!((v[0] - 20.0 * 47.6_f64.log10()).abs() < 0.5);
+        assert!(lisn.current_to_voltage(&[30e6], &[]).is_err());
+    }
+
+    #[test]
+    fn test_transducer_table() {
+        let table = LisnModel::Cispr25_5uH.vdf_transducer(150e3, 108e6, 10);
+        assert!(table.points.len() > 20);
+        assert!(LisnModel::parse("CISPR25").is_ok());
+        assert!(LisnModel::parse("100uH").is_err());
+    }
+}
@@ -0,0 +1,144 @@
+// wasm/src/measurement.rs
+// Measured spectra from CSV / text traces: a frequency column plus one level column per detector
+use serde::{Deserialize, Serialize};
+
+use crate::limit_lines::{detector_of, frequency_scale, parse_number, split_fields};
+use crate::{Detector, EmcError};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trace {
+    pub detector: Option<Detector>,  // from the column header; None for an unnamed level column
+    pub amplitudes: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Measurement {
+    pub frequencies: Vec<f64>,  // Hz
+    pub traces: Vec<Trace>,
+    pub frequency_unit: String,  // unit of the source file
+}
+
+impl Measurement {
+    // Levels for a detector: its named column, or the first column of a file without detector names
+    pub fn trace(&self, detector: Detector) -> Result<&[f64], EmcError> {
+        if let Some(trace) = self.traces.iter().find(|t| t.detector == Some(detector)) {
+            return Ok(&trace.amplitudes);
+        }
+        if self.traces.iter().all(|t| t.detector.is_none()) {
+            return Ok(&self.traces[0].amplitudes);
+        }
+        let named: Vec<&str> = self.traces.iter().filter_map(|t| t.detector.map(Detector::as_str)).collect();
+        Err(EmcError::invalid(format!("No '{}' column in the measurement (has {})", detector, named.join(", "))))
+    }
+}
+
+// Header rows are recognised by a non-numeric first field; "# ..." lines are comments.
+// Frequencies are in Hz unless the header or `frequency_unit` says otherwise.
+pub fn parse_measurement(text: &str, frequency_unit: Option<&str>) -> Result<Measurement, EmcError> {
+    let mut header: Option<Vec<Option<Detector>>> = None;
+    let mut hint: Option<(f64, &'static str)> = None;
+    let mut frequencies = Vec::new();
+    let mut columns: Vec<Vec<f64>> = Vec::new();
+
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if trimmed.starts_with('#') || trimmed.starts_with("//") {
+            hint = hint.or_else(|| frequency_scale(trimmed));
+            continue;
+        }
+
+        let fields = split_fields(trimmed);
+        if fields[0].parse::<f64>().is_err() {
+            if !frequencies.is_empty() {
+                return Err(EmcError::parse(line, None, "unexpected header after data rows"));
+            }
+            hint = frequency_scale(fields[0]).or(hint);
+            // A units-only row (e.g. "[MHz];[dBuV]") keeps the detector names of the row above
+            let detectors: Vec<Option<Detector>> = fields[1..].iter().map(|f| detector_of(f)).collect();
+            if header.is_none() || detectors.iter().any(Option::is_some) {
+                header = Some(detectors);
+            }
+            continue;
+        }
+
+        if fields.len() < 2 {
+            return Err(EmcError::parse(line, None, "expected a frequency and at least one level"));
+        }
+        if columns.is_empty() {
+            columns = vec![Vec::new(); fields.len() - 1];
+        }
+        if fields.len() - 1 < columns.len() {
+            return Err(EmcError::parse(line, None, format!("expected {} levels, found {}", columns.len(), fields.len() - 1)));
+        }
+
+        frequencies.push(parse_number(fields[0], raw, line)?.unwrap_or(f64::NAN));
+        for (column, levels) in columns.iter_mut().enumerate() {
+            levels.push(parse_number(fields[column + 1], raw, line)?.unwrap_or(f64::NAN));
+        }
+    }
+
+    if frequencies.is_empty() {
+        return Err(EmcError::invalid("No data rows found"));
+    }
+
+    let (scale, unit) = match frequency_unit {
+        Some(unit) => frequency_scale(unit).ok_or_else(|| EmcError::invalid(format!("Unknown frequency unit '{}'", unit)))?,
+        None => hint.unwrap_or((1.0, "Hz")),
+    };
+
+    let names = header.unwrap_or_default();
+    let traces = columns
+        .into_iter()
+        .enumerate()
+        .map(|(column, amplitudes)| Trace { detector: names.get(column).copied().flatten(), amplitudes })
+        .collect();
+
+    Ok(Measurement {
+        frequencies: frequencies.iter().map(|f| f * scale).collect(),
+        traces,
+        frequency_unit: unit.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_column_trace() {
+        let text = "Frequency(Hz), Amplitude(dBµV)\n150000, 45.2\n500000, 40.5\n1000000, 42.1\n";
+        let measurement = parse_measurement(text, None).unwrap();
+
+        assert_eq!(measurement.frequencies, vec![150e3, 500e3, 1e6]);
+        assert_eq!(measurement.frequency_unit, "Hz");
+        assert_eq!(measurement.trace(Detector::Qp).unwrap(), &[45.2, 40.5, 42.1]);
+    }
+
+    #[test]
+    fn test_detector_columns_and_units() {
+        let text = "Frequency;Peak;QP;Avg\n[MHz];[dBuV];[dBuV];[dBuV]\n0.15;60;55;50\n30;40;35;30\n";
+        let measurement = parse_measurement(text, None).unwrap();
+
+        assert_eq!(measurement.frequencies, vec![150e3, 30e6]);
+        assert_eq!(measurement.trace("AVG".parse().unwrap()).unwrap(), &[50.0, 30.0]);
+        assert_eq!(measurement.trace(Detector::Pk).unwrap(), &[60.0, 40.0]);
+
+        // Explicit unit overrides the header
+        let overridden = parse_measurement(text, Some("kHz")).unwrap();
+        assert_eq!(overridden.frequencies[0], 150.0);
+    }
+
+    #[test]
+    fn test_missing_detector_and_errors() {
+        let measurement = parse_measurement("f,peak,avg\n1e6,50,40\n", None).unwrap();
+        assert!(measurement.trace(Detector::Qp).unwrap_err().to_string().contains("pk, avg"));
+
+        assert!(parse_measurement("# only a comment\n", None).is_err());
+        assert!(parse_measurement("1e6;50;40\n2e6;55\n", None).unwrap_err().to_string().contains("line 2"));
+        assert_eq!(parse_measurement("1e6,abc\n", None).unwrap_err(), EmcError::parse(1, Some(5), "'abc' is not a number"));
+    }
+}
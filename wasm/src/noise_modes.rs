@@ -0,0 +1,154 @@
+// wasm/src/noise_modes.rs
+// Common-mode / differential-mode separation of conducted emissions
+use serde::{Deserialize, Serialize};
+
+use crate::{ComplianceResult, Detector, EMCStandard, EmcError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoiseMode {
+    CommonMode,
+    DifferentialMode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModeSpectra {
+    pub frequencies: Vec<f64>,
+    pub cm: Vec<f64>,  // dBµV
+    pub dm: Vec<f64>,  // dBµV
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModeComplianceReport {
+    pub spectra: ModeSpectra,
+    pub cm_results: Vec<ComplianceResult>,
+    pub dm_results: Vec<ComplianceResult>,
+    pub cm_worst_margin: f64,
+    pub dm_worst_margin: f64,
+    pub dominant_mode: NoiseMode,  // mode with the smaller worst-case margin
+}
+
+fn dbuv_to_microvolts(dbuv: f64) -> f64 {
+    10.0_f64.powf(dbuv / 20.0)
+}
+
+fn microvolts_to_dbuv(microvolts: f64) -> f64 {
+    20.0 * microvolts.max(f64::MIN_POSITIVE).log10()
+}
+
+fn check_lengths(frequencies: &[f64], traces: &[&[f64]]) -> Result<(), EmcError> {
+    match traces.iter().find(|trace| trace.len() != frequencies.len()) {
+        Some(trace) => Err(EmcError::LengthMismatch { field: "traces".to_string(), expected: frequencies.len(), found: trace.len() }),
+        None => Ok(()),
+    }
+}
+
+// CM = (V_L + V_N) / 2 and DM = (V_L - V_N) / 2 from phase-resolved line measurements
+pub fn separate_from_lines(
+    frequencies: &[f64],
+    line_dbuv: &[f64],
+    line_phase_deg: &[f64],
+    neutral_dbuv: &[f64],
+    neutral_phase_deg: &[f64],
+) -> Result<ModeSpectra, EmcError> {
+    check_lengths(frequencies, &[line_dbuv, line_phase_deg, neutral_dbuv, neutral_phase_deg])?;
+
+    let mut cm = Vec::with_capacity(frequencies.len());
+    let mut dm = Vec::with_capacity(frequencies.len());
+
+    for i in 0..frequencies.len() {
+        let (l_im, l_re) = line_phase_deg[i].to_radians().sin_cos();
+        let (n_im, n_re) = neutral_phase_deg[i].to_radians().sin_cos();
+        let l = dbuv_to_microvolts(line_dbuv[i]);
+        let n = dbuv_to_microvolts(neutral_dbuv[i]);
+
+        let (l_re, l_im) = (l * l_re, l * l_im);
+        let (n_re, n_im) = (n * n_re, n * n_im);
+
+        cm.push(microvolts_to_dbuv(0.5 * (l_re + n_re).hypot(l_im + n_im)));
+        dm.push(microvolts_to_dbuv(0.5 * (l_re - n_re).hypot(l_im - n_im)));
+    }
+
+    Ok(ModeSpectra { frequencies: frequencies.to_vec(), cm, dm })
+}
+
+// CM/DM spectra from a hardware separator's outputs plus its correction factors (dB)
+pub fn separate_from_separator(
+    frequencies: &[f64],
+    cm_output: &[f64],
+    dm_output: &[f64],
+    cm_correction: f64,
+    dm_correction: f64,
+) -> Result<ModeSpectra, EmcError> {
+    check_lengths(frequencies, &[cm_output, dm_output])?;
+
+    Ok(ModeSpectra {
+        frequencies: frequencies.to_vec(),
+        cm: cm_output.iter().map(|a| a + cm_correction).collect(),
+        dm: dm_output.iter().map(|a| a + dm_correction).collect(),
+    })
+}
+
+// Check each mode against the standard's limits
+pub fn check_modes(standard: &EMCStandard, spectra: ModeSpectra, detector: Detector) -> Result<ModeComplianceReport, EmcError> {
+    let cm_results = standard.check_compliance(&spectra.frequencies, &spectra.cm, detector)?;
+    let dm_results = standard.check_compliance(&spectra.frequencies, &spectra.dm, detector)?;
+
+    let worst = |results: &[ComplianceResult]| results.iter().map(|r| r.margin).fold(f64::INFINITY, f64::min);
+    let cm_worst_margin = worst(&cm_results);
+    let dm_worst_margin = worst(&dm_results);
+
+    let dominant_mode = if dm_worst_margin < cm_worst_margin {
+        NoiseMode::DifferentialMode
+    } else {
+        NoiseMode::CommonMode
+    };
+
+    Ok(ModeComplianceReport {
+        spectra,
+        cm_results,
+        dm_results,
+        cm_worst_margin,
+        dm_worst_margin,
+        dominant_mode,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_phase_lines_are_common_mode() {
+        let spectra = separate_from_lines(&[1e6], &[60.0], &[30.0], &[60.0], &[30.0]).unwrap();
+
+        assert!((spectra.cm[0] - 60.0).abs() < 1e-9);
+        assert!(spectra.dm[0] < -100.0);
+    }
+
+    #[test]
+    fn test_antiphase_lines_are_differential_mode() {
+        let spectra = separate_from_lines(&[1e6, 2e6], &[60.0, 50.0], &[0.0, 90.0], &[60.0, 50.0], &[180.0, -90.0]).unwrap();
+
+        assert!(spectra.cm.iter().all(|&cm| cm < -100.0));
+        assert!((spectra.dm[0] - 60.0).abs() < 1e-9);
+        assert!((spectra.dm[1] - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mode_compliance_report() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let frequencies = vec![500e3, 1e6, 10e6];
+        let spectra = separate_from_separator(&frequencies, &[40.0, 40.0, 40.0], &[20.0, 70.0, 20.0], 0.0, 3.0).unwrap();
+
+        assert_eq!(spectra.dm[1], 73.0);
+        let report = check_modes(&standard, spectra, Detector::Avg).unwrap();
+        assert_eq!(report.dominant_mode, NoiseMode::DifferentialMode);
+        assert!(report.dm_worst_margin < 0.0);
+        assert!(report.cm_results.iter().all(|r| r.passes));
+    }
+
+    #[test]
+    fn test_length_mismatch_rejected() {
+        assert!(separate_from_separator(&[1e6, 2e6], &[40.0], &[40.0, 40.0], 0.0, 0.0).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+// wasm/src/scan_plan.rs
+// Band-aware scan planning following the CISPR 16-1-1 receiver settings
+use serde::{Deserialize, Serialize};
+
+use crate::frequency_helpers::frequency_bands::CisprBand;
+use crate::EMCStandard;
+
+// Minimum scan rates (s/Hz) from the CISPR 16-2 scan time tables
+fn pk_scan_rate(band: CisprBand) -> f64 {
+    match band {
+        CisprBand::A => 100e-3 / 1e3,   // 100 ms/kHz
+        CisprBand::B => 100e-3 / 1e6,   // 100 ms/MHz
+        CisprBand::C | CisprBand::D | CisprBand::E => 1e-3 / 1e6, // 1 ms/MHz
+    }
+}
+
+fn qp_scan_rate(band: CisprBand) -> Option<f64> {
+    match band {
+        CisprBand::A => Some(20.0 / 1e3),  // 20 s/kHz
+        CisprBand::B => Some(200.0 / 1e6), // 200 s/MHz
+        CisprBand::C | CisprBand::D => Some(20.0 / 1e6), // 20 s/MHz
+        CisprBand::E => None,
+    }
+}
+
+// Receiver settings and timing for the part of a scan falling into one band
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandScanSegment {
+    pub band: CisprBand,
+    pub f_start: f64,
+    pub f_stop: f64,
+    pub rbw: f64,
+    pub step: f64,
+    pub points: usize,
+    pub pk_dwell_time: f64,          // s per step
+    pub qp_dwell_time: Option<f64>,  // s per step, None above 1 GHz
+    pub avg_dwell_time: f64,         // s per step
+    pub pk_scan_time: f64,           // s
+    pub qp_scan_time: Option<f64>,   // s
+    pub avg_scan_time: f64,          // s
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanPlan {
+    pub standard: String,
+    pub f_start: f64,
+    pub f_stop: f64,
+    pub segments: Vec<BandScanSegment>,
+    pub total_pk_scan_time: f64,
+    pub total_qp_scan_time: f64,  // only segments where QP is defined
+    pub total_avg_scan_time: f64,
+}
+
+impl BandScanSegment {
+    pub fn new(band: CisprBand, f_start: f64, f_stop: f64) -> Self {
+        let rbw = band.rbw();
+        // Half the RBW so narrowband emissions cannot fall between two steps
+        let step = rbw / 2.0;
+        let points = ((f_stop - f_start) / step).ceil() as usize + 1;
+
+        let pk_dwell_time = pk_scan_rate(band) * step;
+        let qp_dwell_time = qp_scan_rate(band).map(|rate| rate * step);
+        let avg_dwell_time = band.meter_time_constant();
+
+        Self {
+            band,
+            f_start,
+            f_stop,
+            rbw,
+            step,
+            points,
+            pk_dwell_time,
+            qp_dwell_time,
+            avg_dwell_time,
+            pk_scan_time: pk_dwell_time * points as f64,
+            qp_scan_time: qp_dwell_time.map(|dwell| dwell * points as f64),
+            avg_scan_time: avg_dwell_time * points as f64,
+        }
+    }
+}
+
+// Split the requested range (clipped to the standard's mask span) into CISPR bands
+pub fn plan_scan(standard: &EMCStandard, f_min: f64, f_max: f64) -> ScanPlan {
+    let (mask_min, mask_max) = standard.frequency_span();
+    let f_start = f_min.max(mask_min);
+    let f_stop = f_max.min(mask_max);
+
+    let mut segments = Vec::new();
+    for band in CisprBand::ALL {
+        let (band_start, band_end) = band.range();
+        let seg_start = f_start.max(band_start);
+        let seg_stop = f_stop.min(band_end);
+
+        if seg_start < seg_stop {
+            segments.push(BandScanSegment::new(band, seg_start, seg_stop));
+        }
+    }
+
+    ScanPlan {
+        standard: standard.name.clone(),
+        f_start,
+        f_stop,
+        total_pk_scan_time: segments.iter().map(|s| s.pk_scan_time).sum(),
+        total_qp_scan_time: segments.iter().filter_map(|s| s.qp_scan_time).sum(),
+        total_avg_scan_time: segments.iter().map(|s| s.avg_scan_time).sum(),
+        segments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_band_b_plan() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let plan = plan_scan(&standard, 150_000.0, 30_000_000.0);
+
+        assert_eq!(plan.segments.len(), 1);
+        let segment = &plan.segments[0];
+        assert_eq!(segment.band, CisprBand::B);
+        assert_eq!(segment.rbw, 9_000.0);
+        assert_eq!(segment.step, 4_500.0);
+
+        // CISPR 16-2: ~3 s peak and ~6000 s quasi-peak for the whole of band B
+        assert!((plan.total_pk_scan_time - 2.985).abs() < 0.01);
+        assert!((plan.total_qp_scan_time - 5970.0).abs() < 5.0);
+        assert!(plan.total_avg_scan_time > plan.total_pk_scan_time);
+    }
+
+    #[test]
+    fn test_plan_spans_multiple_bands() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassA").unwrap();
+        let plan = plan_scan(&standard, 9_000.0, 18e9);
+
+        // Clipped to the mask span (150 kHz - 1 GHz)
+        assert_eq!(plan.f_start, 150_000.0);
+        assert_eq!(plan.f_stop, 1e9);
+
+        let bands: Vec<CisprBand> = plan.segments.iter().map(|s| s.band).collect();
+        assert_eq!(bands, vec![CisprBand::B, CisprBand::C, CisprBand::D]);
+
+        for pair in plan.segments.windows(2) {
+            assert_eq!(pair[0].f_stop, pair[1].f_start);
+        }
+    }
+
+    #[test]
+    fn test_plan_outside_mask_is_empty() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassA").unwrap();
+        let plan = plan_scan(&standard, 10_000.0, 100_000.0);
+
+        assert!(plan.segments.is_empty());
+        assert_eq!(plan.total_pk_scan_time, 0.0);
+    }
+}
@@ -0,0 +1,272 @@
+// wasm/src/time_domain.rs
+// FFT-based receiver emulation: time-domain capture -> EMI spectrum in dBµV
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+use crate::scan_plan::plan_scan;
+use crate::{EMCStandard, EmcError};
+
+// Reference voltage for dBµV conversion
+const MICROVOLT: f64 = 1e-6;
+
+// Gaussian receiver filter is evaluated out to this many RBWs from the tuned frequency
+const FILTER_SPAN_RBW: f64 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowType {
+    Rectangular,
+    Hann,
+    FlatTop,
+}
+
+impl WindowType {
+    pub fn parse(name: &str) -> Result<WindowType, EmcError> {
+        match name.to_lowercase().as_str() {
+            "rectangular" | "rect" | "none" => Ok(WindowType::Rectangular),
+            "hann" | "hanning" => Ok(WindowType::Hann),
+            "flattop" | "flat_top" => Ok(WindowType::FlatTop),
+            _ => Err(EmcError::invalid("window must be 'rectangular', 'hann' or 'flattop'")),
+        }
+    }
+
+    pub fn coefficients(&self, n: usize) -> Vec<f64> {
+        if n < 2 {
+            return vec![1.0; n];
+        }
+
+        let m = (n - 1) as f64;
+        (0..n)
+            .map(|i| {
+                let x = 2.0 * PI * i as f64 / m;
+                match self {
+                    WindowType::Rectangular => 1.0,
+                    WindowType::Hann => 0.5 - 0.5 * x.cos(),
+                    WindowType::FlatTop => {
+                        0.21557895 - 0.41663158 * x.cos() + 0.277263158 * (2.0 * x).cos()
+                            - 0.083578947 * (3.0 * x).cos()
+                            + 0.006947368 * (4.0 * x).cos()
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmiSpectrum {
+    pub frequencies: Vec<f64>,  // Hz
+    pub amplitudes: Vec<f64>,   // dBµV (RMS within the CISPR bandwidth)
+    pub rbw: Vec<f64>,          // Hz, receiver bandwidth used at each frequency
+    pub window: WindowType,
+    pub frequency_resolution: f64,  // Hz, FFT bin spacing
+}
+
+// RMS voltage to dBµV, floored so that silence stays finite
+pub fn volts_to_dbuv(volts: f64) -> f64 {
+    20.0 * (volts.max(f64::MIN_POSITIVE) / MICROVOLT).log10()
+}
+
+// In-place iterative radix-2 FFT; the length must be a power of two
+pub fn fft(re: &mut [f64], im: &mut [f64]) {
+    let n = re.len();
+    debug_assert!(n.is_power_of_two() && im.len() == n);
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (w_im, w_re) = (angle * k as f64).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+// Inverse FFT (scaled by 1/n) via the conjugation identity
+pub fn ifft(re: &mut [f64], im: &mut [f64]) {
+    let n = re.len() as f64;
+    im.iter_mut().for_each(|x| *x = -*x);
+    fft(re, im);
+    re.iter_mut().for_each(|x| *x /= n);
+    im.iter_mut().for_each(|x| *x = -*x / n);
+}
+
+// Single-sided RMS amplitude per FFT bin, corrected for the window's coherent gain.
+// Returns (bin amplitudes in V, bin spacing in Hz, window ENBW in bins).
+pub fn amplitude_spectrum(samples: &[f64], sample_rate: f64, window: WindowType) -> (Vec<f64>, f64, f64) {
+    let n = samples.len();
+    let coefficients = window.coefficients(n);
+    let sum_w: f64 = coefficients.iter().sum();
+    let sum_w2: f64 = coefficients.iter().map(|w| w * w).sum();
+    let enbw = n as f64 * sum_w2 / (sum_w * sum_w);
+
+    let n_fft = n.next_power_of_two();
+    let mut re = vec![0.0; n_fft];
+    let mut im = vec![0.0; n_fft];
+    for (i, (&x, &w)) in samples.iter().zip(coefficients.iter()).enumerate() {
+        re[i] = x * w;
+    }
+    fft(&mut re, &mut im);
+
+    // Zero padding interpolates the spectrum, so ENBW in bins scales with n_fft / n
+    let enbw_bins = enbw * n_fft as f64 / n as f64;
+    let scale = 2.0 / sum_w / 2.0_f64.sqrt();
+    let amplitudes = (0..=n_fft / 2)
+        .map(|k| {
+            let magnitude = (re[k] * re[k] + im[k] * im[k]).sqrt() * scale;
+            if k == 0 { magnitude / 2.0_f64.sqrt() } else { magnitude }
+        })
+        .collect();
+
+    (amplitudes, sample_rate / n_fft as f64, enbw_bins)
+}
+
+// Voltage response of a Gaussian IF filter; 0.5 (-6 dB) at +/- rbw/2
+pub fn gaussian_filter_gain(offset: f64, rbw: f64) -> f64 {
+    let x = 2.0 * offset / rbw;
+    (-std::f64::consts::LN_2 * x * x).exp()
+}
+
+// Level seen by a receiver with a Gaussian filter of 6 dB bandwidth `rbw` tuned to `frequency`
+fn receiver_level(bins: &[f64], bin_spacing: f64, enbw_bins: f64, frequency: f64, rbw: f64) -> f64 {
+    let lo = ((frequency - FILTER_SPAN_RBW * rbw) / bin_spacing).floor().max(0.0) as usize;
+    let hi = (((frequency + FILTER_SPAN_RBW * rbw) / bin_spacing).ceil() as usize).min(bins.len() - 1);
+
+    let mut power = 0.0;
+    for (k, &amplitude) in bins.iter().enumerate().take(hi + 1).skip(lo) {
+        let gain = gaussian_filter_gain(k as f64 * bin_spacing - frequency, rbw);
+        power += (gain * amplitude).powi(2);
+    }
+
+    (power / enbw_bins).sqrt()
+}
+
+// Emulate a CISPR receiver sweep over the standard's frequency range
+pub fn capture_to_spectrum(
+    standard: &EMCStandard,
+    sample_rate: f64,
+    samples: &[f64],
+    window: WindowType,
+) -> Result<EmiSpectrum, EmcError> {
+    if samples.len() < 2 {
+        return Err(EmcError::invalid("At least two samples are required"));
+    }
+    if !sample_rate.is_finite() || sample_rate <= 0.0 {
+        return Err(EmcError::invalid("sample_rate must be positive"));
+    }
+
+    let (bins, bin_spacing, enbw_bins) = amplitude_spectrum(samples, sample_rate, window);
+    let plan = plan_scan(standard, 0.0, sample_rate / 2.0);
+
+    let mut frequencies = Vec::new();
+    let mut amplitudes = Vec::new();
+    let mut rbw = Vec::new();
+
+    for segment in &plan.segments {
+        if bin_spacing > segment.rbw / 2.0 {
+            return Err(EmcError::invalid(format!(
+                "Capture too short: {:.1} Hz FFT resolution cannot resolve the {:.0} Hz bandwidth of band {:?}",
+                bin_spacing, segment.rbw, segment.band
+            )));
+        }
+
+        for i in 0..segment.points {
+            let freq = (segment.f_start + i as f64 * segment.step).min(segment.f_stop);
+            // Shared band edges are only emitted once
+            if frequencies.last().is_some_and(|&last| freq <= last) {
+                continue;
+            }
+
+            let level = receiver_level(&bins, bin_spacing, enbw_bins, freq, segment.rbw);
+            frequencies.push(freq);
+            amplitudes.push(volts_to_dbuv(level));
+            rbw.push(segment.rbw);
+        }
+    }
+
+    Ok(EmiSpectrum {
+        frequencies,
+        amplitudes,
+        rbw,
+        window,
+        frequency_resolution: bin_spacing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(sample_rate: f64, n: usize, frequency: f64, amplitude: f64) -> Vec<f64> {
+        (0..n)
+            .map(|i| amplitude * (2.0 * PI * frequency * i as f64 / sample_rate).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_fft_single_tone() {
+        let n = 64;
+        let mut re: Vec<f64> = (0..n).map(|i| (2.0 * PI * 4.0 * i as f64 / n as f64).cos()).collect();
+        let mut im = vec![0.0; n];
+        fft(&mut re, &mut im);
+
+        assert!((re[4] - n as f64 / 2.0).abs() < 1e-9);
+        assert!(re[5].abs() < 1e-9 && im[5].abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_window_parse() {
+        assert_eq!(WindowType::parse("Hann").unwrap(), WindowType::Hann);
+        assert_eq!(WindowType::parse("flattop").unwrap(), WindowType::FlatTop);
+        assert!(WindowType::parse("kaiser").is_err());
+    }
+
+    #[test]
+    fn test_sine_level_in_dbuv() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let sample_rate = 20e6;
+        // 1 mV peak at 1 MHz -> 0.707 mV RMS -> 57 dBµV
+        let samples = sine(sample_rate, 1 << 14, 1e6, 1e-3);
+
+        let spectrum = capture_to_spectrum(&standard, sample_rate, &samples, WindowType::FlatTop).unwrap();
+        assert_eq!(spectrum.frequencies.len(), spectrum.amplitudes.len());
+        assert!(spectrum.frequencies.iter().all(|&f| f <= sample_rate / 2.0));
+
+        let (idx, peak) = spectrum.amplitudes.iter().enumerate()
+            .fold((0, f64::NEG_INFINITY), |acc, (i, &a)| if a > acc.1 { (i, a) } else { acc });
+        assert!((spectrum.frequencies[idx] - 1e6).abs() <= 9_000.0);
+        assert!((peak - 57.0).abs() < 1.0, "peak = {}", peak);
+    }
+
+    #[test]
+    fn test_short_capture_rejected() {
+        let standard = EMCStandard::get_standard("CISPR22", "ClassB").unwrap();
+        let samples = sine(20e6, 256, 1e6, 1e-3);
+
+        assert!(capture_to_spectrum(&standard, 20e6, &samples, WindowType::Hann).is_err());
+    }
+}
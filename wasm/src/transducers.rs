@@ -0,0 +1,58 @@
+// wasm/src/transducers.rs
+// Frequency-dependent correction factors applied to receiver readings
+use serde::{Deserialize, Serialize};
+
+use crate::{log_interp, EmcError};
+
+// Correction factor table: (frequency_hz, factor_db), added to the receiver reading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransducerTable {
+    pub name: String,
+    pub points: Vec<(f64, f64)>,
+}
+
+impl TransducerTable {
+    pub fn new(name: &str, points: Vec<(f64, f64)>) -> Self {
+        Self { name: name.to_string(), points }
+    }
+
+    // Log-frequency interpolated factor, held constant outside the table
+    pub fn factor_at(&self, frequency: f64) -> f64 {
+        let (frequencies, factors): (Vec<f64>, Vec<f64>) = self.points.iter().cloned().unzip();
+        log_interp(&frequencies, &factors, frequency)
+    }
+
+    // Corrected trace: reading + factor at each frequency
+    pub fn apply(&self, frequencies: &[f64], amplitudes: &[f64]) -> Result<Vec<f64>, EmcError> {
+        crate::validation::check_lengths(frequencies, amplitudes)?;
+
+        let (table_f, table_db): (Vec<f64>, Vec<f64>) = self.points.iter().cloned().unzip();
+        Ok(frequencies
+            .iter()
+            .zip(amplitudes.iter())
+            .map(|(&f, &a)| a + log_interp(&table_f, &table_db, f))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factor_interpolation() {
+        let table = TransducerTable::new("test", vec![(1e5, 10.0), (1e7, 0.0)]);
+
+        assert_eq!(table.factor_at(1e4), 10.0);
+        assert!((table.factor_at(1e6) - 5.0).abs() < 1e-9);
+        assert_eq!(table.factor_at(1e8), 0.0);
+    }
+
+    #[test]
+    fn test_apply() {
+        let table = TransducerTable::new("test", vec![(1e5, 10.0), (1e7, 0.0)]);
+
+        assert_eq!(table.apply(&[1e5, 1e7], &[40.0, 40.0]).unwrap(), vec![50.0, 40.0]);
+        assert!(table.apply(&[1e5], &[40.0, 40.0]).is_err());
+    }
+}
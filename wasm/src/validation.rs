@@ -0,0 +1,161 @@
+// wasm/src/validation.rs
+// Measured-trace validation: length, finiteness, positive and ascending frequencies, optional clean-up
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::EmcError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PointIssue {
+    NonFiniteFrequency,
+    NonPositiveFrequency,
+    NonFiniteAmplitude,
+    OutOfOrder,  // frequency below an earlier point's
+}
+
+impl fmt::Display for PointIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PointIssue::NonFiniteFrequency => "frequency is not a finite number",
+            PointIssue::NonPositiveFrequency => "frequency must be positive",
+            PointIssue::NonFiniteAmplitude => "amplitude is not a finite number",
+            PointIssue::OutOfOrder => "frequencies must be in ascending order",
+        })
+    }
+}
+
+// Strict by default: any issue is an error
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValidationOptions {
+    pub sort: bool,  // reorder by frequency instead of rejecting unsorted input
+    pub drop_invalid: bool,  // drop non-finite / non-positive rows instead of rejecting them
+}
+
+impl ValidationOptions {
+    pub fn lenient() -> ValidationOptions {
+        ValidationOptions { sort: true, drop_invalid: true }
+    }
+}
+
+// A row that was dropped or moved; `index` refers to the input arrays
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub index: usize,
+    pub frequency: f64,
+    pub amplitude: f64,
+    pub issue: PointIssue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidatedTrace {
+    pub frequencies: Vec<f64>,
+    pub amplitudes: Vec<f64>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+pub fn check_lengths(frequencies: &[f64], amplitudes: &[f64]) -> Result<(), EmcError> {
+    if frequencies.len() != amplitudes.len() {
+        return Err(EmcError::LengthMismatch { field: "amplitudes".to_string(), expected: frequencies.len(), found: amplitudes.len() });
+    }
+    Ok(())
+}
+
+fn point_issue(frequency: f64, amplitude: f64) -> Option<PointIssue> {
+    if !frequency.is_finite() {
+        Some(PointIssue::NonFiniteFrequency)
+    } else if frequency <= 0.0 {
+        Some(PointIssue::NonPositiveFrequency)
+    } else if !amplitude.is_finite() {
+        Some(PointIssue::NonFiniteAmplitude)
+    } else {
+        None
+    }
+}
+
+// Validated copy of a trace; lengths must always match and at least one valid point must remain
+pub fn validate_trace(frequencies: &[f64], amplitudes: &[f64], options: &ValidationOptions) -> Result<ValidatedTrace, EmcError> {
+    check_lengths(frequencies, amplitudes)?;
+    if frequencies.is_empty() {
+        return Err(EmcError::invalid("The measurement has no points"));
+    }
+
+    let mut diagnostics = Vec::new();
+    let mut rows: Vec<usize> = Vec::with_capacity(frequencies.len());
+    for (index, (&frequency, &amplitude)) in frequencies.iter().zip(amplitudes).enumerate() {
+        match point_issue(frequency, amplitude) {
+            Some(issue) if options.drop_invalid => diagnostics.push(Diagnostic { index, frequency, amplitude, issue }),
+            Some(issue) => return Err(EmcError::InvalidPoint { index, issue }),
+            None => rows.push(index),
+        }
+    }
+    if rows.is_empty() {
+        return Err(EmcError::invalid("The measurement has no valid points"));
+    }
+
+    let mut highest = f64::NEG_INFINITY;
+    for &index in &rows {
+        let frequency = frequencies[index];
+        if frequency < highest {
+            if !options.sort {
+                return Err(EmcError::InvalidPoint { index, issue: PointIssue::OutOfOrder });
+            }
+            diagnostics.push(Diagnostic { index, frequency, amplitude: amplitudes[index], issue: PointIssue::OutOfOrder });
+        }
+        highest = highest.max(frequency);
+    }
+    // Stable, so equal frequencies keep their order
+    rows.sort_by(|&a, &b| frequencies[a].total_cmp(&frequencies[b]));
+    diagnostics.sort_by_key(|d| d.index);
+
+    Ok(ValidatedTrace {
+        frequencies: rows.iter().map(|&i| frequencies[i]).collect(),
+        amplitudes: rows.iter().map(|&i| amplitudes[i]).collect(),
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strict_validation() {
+        let error = validate_trace(&[1e6, 2e6], &[40.0], &ValidationOptions::default()).unwrap_err();
+        assert_eq!(error, EmcError::LengthMismatch { field: "amplitudes".into(), expected: 2, found: 1 });
+
+        let error = validate_trace(&[1e6, 2e6, 3e6], &[40.0, f64::NAN, 42.0], &ValidationOptions::default()).unwrap_err();
+        assert_eq!(error, EmcError::InvalidPoint { index: 1, issue: PointIssue::NonFiniteAmplitude });
+
+        let error = validate_trace(&[2e6, 1e6], &[40.0, 41.0], &ValidationOptions::default()).unwrap_err();
+        assert_eq!(error.code(), "INVALID_POINT");
+        assert!(validate_trace(&[], &[], &ValidationOptions::default()).is_err());
+
+        let trace = validate_trace(&[1e6, 1e6, 2e6], &[40.0, 45.0, 41.0], &ValidationOptions::default()).unwrap();
+        assert!(trace.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_sort_and_drop_with_diagnostics() {
+        let frequencies = [3e6, -1.0, 1e6, f64::INFINITY, 2e6];
+        let amplitudes = [43.0, 50.0, 41.0, 50.0, 42.0];
+        let trace = validate_trace(&frequencies, &amplitudes, &ValidationOptions::lenient()).unwrap();
+
+        assert_eq!(trace.frequencies, vec![1e6, 2e6, 3e6]);
+        assert_eq!(trace.amplitudes, vec![41.0, 42.0, 43.0]);
+        let issues: Vec<(usize, PointIssue)> = trace.diagnostics.iter().map(|d| (d.index, d.issue)).collect();
+        assert_eq!(
+            issues,
+            vec![
+                (1, PointIssue::NonPositiveFrequency),
+                (2, PointIssue::OutOfOrder),
+                (3, PointIssue::NonFiniteFrequency),
+                (4, PointIssue::OutOfOrder),
+            ]
+        );
+
+        // Nothing valid left
+        assert!(validate_trace(&[f64::NAN], &[40.0], &ValidationOptions::lenient()).is_err());
+    }
+}